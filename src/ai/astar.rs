@@ -0,0 +1,104 @@
+use crate::ai::controller::{grid_key, next_position, ArenaView, WormController, DIRECTIONS};
+use crate::state::structs::{Direction, Vector2D};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+/// Plans a path to food around every body and hazard in the way, searching up to `lookahead` steps ahead.
+/// Without a path in reach it heads for the searched spot closest to food.
+pub struct AStarController {
+    pub lookahead: usize,
+}
+
+struct Node {
+    position: Vector2D,
+    steps: usize,
+    /// The move out of the head this path starts with
+    first_direction: Direction,
+}
+
+impl WormController for AStarController {
+    fn choose_direction(&mut self, arena: &ArenaView) -> Direction {
+        let head = arena.head();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut open = BinaryHeap::new();
+        let mut visited: HashSet<(i32, i32)> = HashSet::from([grid_key(head)]);
+
+        for direction in arena.turns() {
+            let position = next_position(head, direction);
+            if !arena.is_blocked(position, 1) && visited.insert(grid_key(position)) {
+                push(arena, &mut nodes, &mut open, Node { position, steps: 1, first_direction: direction });
+            }
+        }
+
+        // Closest to food among the nodes searched, in case no food is reachable within the lookahead
+        let mut best: Option<(usize, usize)> = None;
+
+        while let Some(Reverse((_, remaining, index))) = open.pop() {
+            let node = &nodes[index];
+            if arena.food_at(node.position) {
+                return node.first_direction;
+            }
+            if best.is_none_or(|(best_remaining, _)| remaining < best_remaining) {
+                best = Some((remaining, index));
+            }
+            if node.steps >= self.lookahead {
+                continue;
+            }
+
+            let (position, steps, first_direction) = (node.position, node.steps, node.first_direction);
+            for direction in DIRECTIONS {
+                let next = next_position(position, direction);
+                if !arena.is_blocked(next, steps + 1) && visited.insert(grid_key(next)) {
+                    push(arena, &mut nodes, &mut open, Node { position: next, steps: steps + 1, first_direction });
+                }
+            }
+        }
+
+        best.map_or(arena.worm.direction, |(_, index)| nodes[index].first_direction)
+    }
+}
+
+fn push(arena: &ArenaView, nodes: &mut Vec<Node>, open: &mut BinaryHeap<Reverse<(usize, usize, usize)>>, node: Node) {
+    let remaining = arena.steps_to_food(node.position).unwrap_or(0);
+    open.push(Reverse((node.steps + remaining, remaining, nodes.len())));
+    nodes.push(node);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::controller::tests::{food, steps_to_eat, wall};
+    use crate::state::structs::Snake;
+
+    fn controller() -> impl WormController {
+        AStarController { lookahead: 40 }
+    }
+
+    #[test]
+    fn turns_away_from_a_body_right_ahead() {
+        let worm = Snake::new(100.0, 100.0, Direction::Right);
+        let others = [wall([(106.0, 100.0), (112.0, 100.0)])];
+        let foods = [food(160.0, 100.0)];
+
+        let arena = ArenaView { worm: &worm, others: others.iter().collect(), foods: &foods, hazards: &[] };
+        let direction = controller().choose_direction(&arena);
+        assert!(matches!(direction, Direction::Up | Direction::Down), "{:?}", direction);
+    }
+
+    #[test]
+    fn finds_food_around_an_obstacle() {
+        let worm = Snake::new(100.0, 100.0, Direction::Right);
+        let others = [wall((0..11).map(|segment| (118.0, 60.0 + 8.0 * segment as f32)))];
+        let foods = [food(150.0, 100.0)];
+
+        assert!(steps_to_eat(&mut controller(), worm, &others, &foods, 60).is_some());
+    }
+
+    #[test]
+    fn never_turns_back_into_its_neck_for_food_behind_it() {
+        let worm = Snake::new(100.0, 100.0, Direction::Right);
+        let foods = [food(40.0, 100.0)];
+
+        assert!(steps_to_eat(&mut controller(), worm, &[], &foods, 60).is_some());
+    }
+}
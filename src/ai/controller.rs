@@ -0,0 +1,139 @@
+use crate::state::constants::graphics::{SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
+use crate::state::constants::physics::{LOWER_BOUND_X, LOWER_BOUND_Y, UPPER_BOUND_X, UPPER_BOUND_Y};
+use crate::state::core::bounds::{wrap_horizontally, wrap_vertically};
+use crate::state::structs::{Direction, Food, GameState, Hazard, Snake, Vector2D, WormId};
+
+pub const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+/// Steers a worm. Asked for a direction once every time the worm has taken a step.
+pub trait WormController {
+    fn choose_direction(&mut self, arena: &ArenaView) -> Direction;
+}
+
+/// What a controller gets to see of the arena: everything on the field, nothing it could change
+pub struct ArenaView<'a> {
+    /// The worm being steered
+    pub worm: &'a Snake,
+    /// Every other worm still in play
+    pub others: Vec<&'a Snake>,
+    pub foods: &'a [Food],
+    pub hazards: &'a [Hazard],
+}
+
+impl<'a> ArenaView<'a> {
+    pub fn new(game_state: &'a GameState, worm: WormId) -> Self {
+        ArenaView {
+            worm: game_state.snake(worm),
+            others: game_state.living_worms().into_iter().filter(|&other| other != worm).map(|other| game_state.snake(other)).collect(),
+            foods: &game_state.foods,
+            hazards: &game_state.hazards,
+        }
+    }
+
+    pub fn head(&self) -> Vector2D {
+        self.worm.body[0]
+    }
+
+    /// Directions the worm can head in next, which is any but straight back into its own neck
+    pub fn turns(&self) -> impl Iterator<Item = Direction> {
+        let reverse = self.worm.direction.opposite();
+        DIRECTIONS.into_iter().filter(move |&direction| direction != reverse)
+    }
+
+    /// Whether a head at `position` would crash `steps` moves from now. Bodies are taken to stay put
+    /// except for their tail ends, which will have moved on by then.
+    pub fn is_blocked(&self, position: Vector2D, steps: usize) -> bool {
+        let snakes = std::iter::once(self.worm).chain(self.others.iter().copied());
+        let body_hit = snakes.into_iter().any(|snake| {
+            let still_there = snake.body.len().saturating_sub(steps);
+            snake.body[..still_there].iter().any(|segment| overlaps(position, *segment))
+        });
+
+        body_hit || self.hazards.iter().any(|hazard| overlaps(position, hazard.position))
+    }
+
+    /// Whether a head at `position` would eat the food there
+    pub fn food_at(&self, position: Vector2D) -> bool {
+        self.foods.iter().any(|food| (position.x - food.position.x).abs() < 12.0 && (position.y - food.position.y).abs() < 12.0)
+    }
+
+    /// Fewest steps from `position` to within eating distance of any food, ignoring obstacles
+    pub fn steps_to_food(&self, position: Vector2D) -> Option<usize> {
+        self.foods.iter().map(|food| steps_between(position, food.position)).min()
+    }
+}
+
+/// Where a head at `position` ends up after one step towards `direction`
+pub fn next_position(position: Vector2D, direction: Direction) -> Vector2D {
+    let mut next = position;
+    match direction {
+        Direction::Left => next.x -= SNAKE_BODY_WIDTH,
+        Direction::Right => next.x += SNAKE_BODY_WIDTH,
+        Direction::Up => next.y -= SNAKE_BODY_HEIGHT,
+        Direction::Down => next.y += SNAKE_BODY_HEIGHT,
+    }
+    wrap_vertically(&mut next);
+    wrap_horizontally(&mut next);
+    next
+}
+
+/// Positions reached by stepping around only ever differ by whole steps, so rounding is enough to tell them apart
+pub fn grid_key(position: Vector2D) -> (i32, i32) {
+    (position.x.round() as i32, position.y.round() as i32)
+}
+
+/// Same extent as a worm segment, which is what counts as a crash between worms
+fn overlaps(position: Vector2D, segment: Vector2D) -> bool {
+    (position.x - segment.x).abs() < SNAKE_BODY_WIDTH && (position.y - segment.y).abs() < SNAKE_BODY_HEIGHT
+}
+
+/// Steps needed to get within eating distance of `target`, taking the short way round the wrapping arena
+fn steps_between(position: Vector2D, target: Vector2D) -> usize {
+    let wrapped = |distance: f32, span: f32| distance.min(span - distance);
+    let dx = wrapped((position.x - target.x).abs(), UPPER_BOUND_X - LOWER_BOUND_X);
+    let dy = wrapped((position.y - target.y).abs(), UPPER_BOUND_Y - LOWER_BOUND_Y);
+
+    let x_steps = ((dx - 12.0).max(0.0) / SNAKE_BODY_WIDTH).ceil() as usize;
+    let y_steps = ((dy - 12.0).max(0.0) / SNAKE_BODY_HEIGHT).ceil() as usize;
+    x_steps + y_steps
+}
+
+/// Arenas for the controllers' tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn food(x: f32, y: f32) -> Food {
+        Food { kind: crate::state::structs::FoodKind::Normal, position: Vector2D { x, y }, age: 0.0, heading: 0.0, food_sprite_frame_index: 0 }
+    }
+
+    /// Another worm laid out along `segments`, for walling off part of the arena
+    pub fn wall(segments: impl IntoIterator<Item = (f32, f32)>) -> Snake {
+        let mut snake = Snake::new(0.0, 0.0, Direction::Right);
+        snake.body = segments.into_iter().map(|(x, y)| Vector2D { x, y }).collect();
+        snake
+    }
+
+    /// Lets `controller` steer `worm` step by step until it eats, returning how many steps that took,
+    /// or `None` if it crashed or didn't get there in `max_steps`. Fails the test on any turn back into the neck.
+    pub fn steps_to_eat(controller: &mut dyn WormController, mut worm: Snake, others: &[Snake], foods: &[Food], max_steps: usize) -> Option<usize> {
+        for step in 1..=max_steps {
+            let arena = ArenaView { worm: &worm, others: others.iter().collect(), foods, hazards: &[] };
+            let direction = controller.choose_direction(&arena);
+            assert_ne!(direction, worm.direction.opposite(), "turned back into its neck at step {}", step);
+
+            let head = next_position(arena.head(), direction);
+            if arena.is_blocked(head, 1) {
+                return None;
+            }
+            if arena.food_at(head) {
+                return Some(step);
+            }
+
+            worm.body.insert(0, head);
+            worm.body.pop();
+            worm.direction = direction;
+        }
+        None
+    }
+}
@@ -0,0 +1,49 @@
+use crate::ai::astar::AStarController;
+use crate::ai::controller::{grid_key, next_position, ArenaView, WormController, DIRECTIONS};
+use crate::state::structs::{Direction, Vector2D};
+use std::collections::{HashSet, VecDeque};
+
+/// Follows an A* path to food, but only into space with room for the whole worm. When the path would
+/// box it in, it takes whichever move leaves the most room instead. Safe enough to play on its own for hours.
+pub struct FloodFillController {
+    pub pathfinder: AStarController,
+}
+
+impl WormController for FloodFillController {
+    fn choose_direction(&mut self, arena: &ArenaView) -> Direction {
+        let head = arena.head();
+        let needed = arena.worm.body.len() + 1;
+        let room = |direction: Direction| {
+            let next = next_position(head, direction);
+            if arena.is_blocked(next, 1) { 0 } else { reachable_cells(arena, next, needed * 2) }
+        };
+
+        let preferred = self.pathfinder.choose_direction(arena);
+        if room(preferred) >= needed {
+            return preferred;
+        }
+
+        arena.turns().max_by_key(|&direction| room(direction)).unwrap_or(preferred)
+    }
+}
+
+/// How many free cells can be reached from `start`, counting no further than `limit`
+fn reachable_cells(arena: &ArenaView, start: Vector2D, limit: usize) -> usize {
+    let mut visited: HashSet<(i32, i32)> = HashSet::from([grid_key(start)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(position) = queue.pop_front() {
+        if visited.len() >= limit {
+            break;
+        }
+
+        for direction in DIRECTIONS {
+            let next = next_position(position, direction);
+            if !arena.is_blocked(next, 1) && visited.insert(grid_key(next)) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    visited.len()
+}
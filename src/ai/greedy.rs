@@ -0,0 +1,77 @@
+use crate::ai::controller::{next_position, ArenaView, WormController};
+use crate::state::structs::Direction;
+
+/// Heads for the closest food by the straightest route, only turning away from a route that runs
+/// into something within its lookahead
+pub struct GreedyController {
+    pub lookahead: usize,
+}
+
+impl WormController for GreedyController {
+    fn choose_direction(&mut self, arena: &ArenaView) -> Direction {
+        let head = arena.head();
+
+        let mut candidates: Vec<(Direction, usize)> = arena
+            .turns()
+            .map(|direction| {
+                let next = next_position(head, direction);
+                (direction, arena.steps_to_food(next).unwrap_or(0))
+            })
+            .collect();
+        candidates.sort_by_key(|&(_, steps)| steps);
+
+        let clear_ahead = |direction: Direction, steps: usize| {
+            let mut position = head;
+            (1..=steps.max(1)).all(|step| {
+                position = next_position(position, direction);
+                !arena.is_blocked(position, step)
+            })
+        };
+
+        // Best move that stays clear for the whole lookahead, then any move that survives the next step
+        candidates
+            .iter()
+            .find(|&&(direction, _)| clear_ahead(direction, self.lookahead))
+            .or_else(|| candidates.iter().find(|&&(direction, _)| clear_ahead(direction, 1)))
+            .map_or(arena.worm.direction, |&(direction, _)| direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::controller::tests::{food, steps_to_eat, wall};
+    use crate::state::structs::Snake;
+
+    fn controller() -> impl WormController {
+        GreedyController { lookahead: 8 }
+    }
+
+    #[test]
+    fn turns_away_from_a_body_right_ahead() {
+        let worm = Snake::new(100.0, 100.0, Direction::Right);
+        let others = [wall([(106.0, 100.0), (112.0, 100.0)])];
+        let foods = [food(160.0, 100.0)];
+
+        let arena = ArenaView { worm: &worm, others: others.iter().collect(), foods: &foods, hazards: &[] };
+        let direction = controller().choose_direction(&arena);
+        assert!(matches!(direction, Direction::Up | Direction::Down), "{:?}", direction);
+    }
+
+    #[test]
+    fn finds_food_around_an_obstacle() {
+        let worm = Snake::new(100.0, 100.0, Direction::Right);
+        let others = [wall((0..11).map(|segment| (118.0, 60.0 + 8.0 * segment as f32)))];
+        let foods = [food(150.0, 100.0)];
+
+        assert!(steps_to_eat(&mut controller(), worm, &others, &foods, 60).is_some());
+    }
+
+    #[test]
+    fn never_turns_back_into_its_neck_for_food_behind_it() {
+        let worm = Snake::new(100.0, 100.0, Direction::Right);
+        let foods = [food(40.0, 100.0)];
+
+        assert!(steps_to_eat(&mut controller(), worm, &[], &foods, 60).is_some());
+    }
+}
@@ -0,0 +1,131 @@
+pub mod controller;
+pub mod greedy;
+pub mod astar;
+pub mod flood_fill;
+
+use crate::ai::astar::AStarController;
+use crate::ai::controller::{ArenaView, WormController};
+use crate::ai::flood_fill::FloodFillController;
+use crate::ai::greedy::GreedyController;
+use crate::state::structs::{Direction, GameState, RivalControl, WormId};
+use std::collections::VecDeque;
+
+/// The controllers a rival worm can be given
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerKind {
+    Greedy,
+    AStar,
+    /// A* that never heads into space too small for the worm, used by the demo on the title screen
+    FloodFill,
+}
+
+impl ControllerKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "greedy" => Some(ControllerKind::Greedy),
+            "astar" => Some(ControllerKind::AStar),
+            "floodfill" => Some(ControllerKind::FloodFill),
+            _ => None,
+        }
+    }
+
+    pub fn create(&self, difficulty: Difficulty) -> Box<dyn WormController> {
+        match self {
+            ControllerKind::Greedy => Box::new(GreedyController { lookahead: difficulty.lookahead }),
+            ControllerKind::AStar => Box::new(AStarController { lookahead: difficulty.lookahead }),
+            ControllerKind::FloodFill => Box::new(FloodFillController { pathfinder: AStarController { lookahead: difficulty.lookahead } }),
+        }
+    }
+}
+
+/// How sharp a computer-controlled worm is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    /// Seconds between a controller making up its mind and the worm actually turning
+    pub reaction_delay: f32,
+    /// How many steps ahead a controller looks for trouble or food
+    pub lookahead: usize,
+}
+
+impl Difficulty {
+    pub const EASY: Difficulty = Difficulty { reaction_delay: 0.25, lookahead: 2 };
+    pub const NORMAL: Difficulty = Difficulty { reaction_delay: 0.1, lookahead: 8 };
+    pub const HARD: Difficulty = Difficulty { reaction_delay: 0.0, lookahead: 40 };
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Difficulty::EASY),
+            "normal" => Some(Difficulty::NORMAL),
+            "hard" => Some(Difficulty::HARD),
+            _ => None,
+        }
+    }
+}
+
+/// A controller at the wheel of a rival worm, together with the turns it has decided on but not made yet
+pub struct AiPilot {
+    pub controller: Box<dyn WormController>,
+    pub difficulty: Difficulty,
+    /// Turns waiting out the reaction delay, with the seconds each has left
+    pub pending_turns: VecDeque<(f32, Direction)>,
+    /// The worm's step count at the controller's last decision
+    pub decided_at_move: Option<u32>,
+}
+
+impl AiPilot {
+    pub fn new(kind: ControllerKind, difficulty: Difficulty) -> Self {
+        AiPilot {
+            controller: kind.create(difficulty),
+            difficulty,
+            pending_turns: VecDeque::new(),
+            decided_at_move: None,
+        }
+    }
+}
+
+/// Lets every computer-controlled worm decide on its next move, and makes the turns whose reaction delay is up
+pub fn steer_ai_worms(game_state: &mut GameState) {
+    if let Some(mut pilot) = game_state.autopilot.take() {
+        steer(&mut pilot, game_state, WormId::Player);
+        game_state.autopilot = Some(pilot);
+    }
+
+    for index in 0..game_state.rivals.len() {
+        if !game_state.rivals[index].alive {
+            continue;
+        }
+
+        // The pilot steps out while its controller looks at the arena, which includes this very worm
+        let RivalControl::Ai(mut pilot) = std::mem::replace(&mut game_state.rivals[index].control, RivalControl::Keyboard) else {
+            continue;
+        };
+        steer(&mut pilot, game_state, WormId::Rival(index));
+        game_state.rivals[index].control = RivalControl::Ai(pilot);
+    }
+}
+
+fn steer(pilot: &mut AiPilot, game_state: &mut GameState, worm: WormId) {
+    let moves = game_state.snake(worm).moves;
+    if pilot.decided_at_move != Some(moves) {
+        pilot.decided_at_move = Some(moves);
+        let direction = pilot.controller.choose_direction(&ArenaView::new(game_state, worm));
+        pilot.pending_turns.push_back((pilot.difficulty.reaction_delay, direction));
+    }
+
+    let delta_time = game_state.delta_time;
+    for (remaining, _) in pilot.pending_turns.iter_mut() {
+        *remaining -= delta_time;
+    }
+    while let Some(&(remaining, direction)) = pilot.pending_turns.front() {
+        if remaining > 0.0 {
+            break;
+        }
+        pilot.pending_turns.pop_front();
+
+        // A turn decided on a while back may by now point straight into the worm's own neck
+        let snake = game_state.snake_mut(worm);
+        if direction != snake.direction.opposite() {
+            snake.direction = direction;
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use crate::audio::manager::{MusicId, SfxId, SfxSound};
+use crate::audio::mixer::{pan_gains, SfxParams, VoicePool};
+use crate::audio::synth::SynthSource;
+use crate::state::constants::audio::SFX_VOICE_COUNT;
+use rodio::source::{ChannelVolume, EmptyCallback, Zero};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use std::io::Cursor;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Output side of the audio system. The `AudioManager` owns caching and state tracking,
+/// while a backend only knows how to turn preloaded bytes into sound (or pretend to).
+pub trait AudioBackend {
+    /// Block until the output is actually consuming samples, or until `timeout` passes.
+    /// Returns false if the output never became ready.
+    fn wait_until_ready(&self, timeout: Duration) -> bool;
+
+    /// Replace the current music with `data`, looping forever
+    fn play_music(&self, music_id: MusicId, data: Vec<u8>) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Start `data` `start_at` into the track on the idle music deck at zero volume, once or on repeat if `looping`.
+    /// The previously active deck keeps playing until the crossfade completes.
+    fn start_crossfade(&self, music_id: MusicId, data: Vec<u8>, start_at: Duration, looping: bool) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Like `start_crossfade`, but the incoming deck stays silent
+    fn start_crossfade_to_silence(&self);
+
+    /// Balance between the outgoing (0.0) and incoming (1.0) deck. At 1.0 the outgoing deck is stopped.
+    fn set_crossfade(&self, progress: f32);
+
+    /// Play a one-shot sound effect on a free voice, or on one stolen according to `params`.
+    /// Sounds that lose out to higher priority effects are silently dropped.
+    fn play_sfx(&self, sfx_id: SfxId, sound: SfxSound, params: SfxParams) -> Result<(), Box<dyn std::error::Error>>;
+
+    fn stop_music(&self);
+    fn pause_music(&self);
+    fn resume_music(&self);
+    fn set_music_volume(&self, volume: f32);
+    fn get_music_volume(&self) -> f32;
+
+    /// Playback rate of the music, where 1.0 is normal. Raises the pitch along with the tempo.
+    fn set_music_speed(&self, speed: f32);
+
+    /// True once the active music deck has drained (or was never filled)
+    fn is_music_empty(&self) -> bool;
+
+    fn stop_fx(&self);
+    fn is_fx_playing(&self) -> bool;
+
+    /// True while any instance of `sfx_id` is still playing
+    fn is_sfx_playing(&self, sfx_id: SfxId) -> bool;
+}
+
+/// Two music sinks that are crossfaded between. Sinks are replaced rather than reused,
+/// since appending to a stopped rodio sink blocks until its old queue has flushed.
+struct MusicDecks {
+    decks: [Option<Sink>; 2],
+    active: usize,
+    volume: f32,
+    speed: f32,
+    crossfade: f32,
+}
+
+impl MusicDecks {
+    fn set_crossfade_and_stop_outgoing(&mut self, progress: f32) {
+        self.crossfade = progress.clamp(0.0, 1.0);
+        self.apply_volumes();
+
+        if self.crossfade >= 1.0 {
+            if let Some(outgoing) = self.decks[1 - self.active].take() {
+                outgoing.stop();
+            }
+        }
+    }
+
+    fn apply_volumes(&self) {
+        if let Some(sink) = &self.decks[self.active] {
+            sink.set_volume(self.volume * self.crossfade);
+            sink.set_speed(self.speed);
+        }
+        if let Some(sink) = &self.decks[1 - self.active] {
+            sink.set_volume(self.volume * (1.0 - self.crossfade));
+            sink.set_speed(self.speed);
+        }
+    }
+
+    /// Make the idle deck active, leaving the old one to be faded out
+    fn swap(&mut self, incoming: Option<Sink>) {
+        self.active = 1 - self.active;
+        if let Some(stale) = self.decks[self.active].take() {
+            stale.stop();
+        }
+        self.decks[self.active] = incoming;
+        self.crossfade = 0.0;
+        self.apply_volumes();
+    }
+}
+
+/// Plays audio through the default output device using rodio
+pub struct RodioBackend {
+    _stream: OutputStream,
+    stream_handle: OutputStreamHandle,
+    music: Arc<Mutex<MusicDecks>>,
+    fx_voices: Arc<Mutex<VoicePool<Sink>>>,
+}
+
+impl RodioBackend {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        // Get the default audio device with full control
+        let (_stream, stream_handle) = OutputStream::try_default()?;
+
+        // Create dedicated sink for background music, with a second deck filled in when crossfading
+        let music = Arc::new(Mutex::new(MusicDecks {
+            decks: [Some(Sink::try_new(&stream_handle)?), None],
+            active: 0,
+            volume: 1.0,
+            speed: 1.0,
+            crossfade: 1.0,
+        }));
+
+        // FX sinks will be created on-demand, one per voice
+        let fx_voices = Arc::new(Mutex::new(VoicePool::new(SFX_VOICE_COUNT)));
+
+        Ok(RodioBackend {
+            _stream,
+            stream_handle,
+            music,
+            fx_voices,
+        })
+    }
+}
+
+impl AudioBackend for RodioBackend {
+    fn wait_until_ready(&self, timeout: Duration) -> bool {
+        let (ready_tx, ready_rx) = mpsc::channel();
+
+        // Prime the device with a few milliseconds of silence followed by a callback,
+        // which only fires once the mixer has pulled the silence through
+        let Ok(priming_sink) = Sink::try_new(&self.stream_handle) else {
+            return false;
+        };
+        priming_sink.append(Zero::<f32>::new_samples(1, 44_100, 441));
+        priming_sink.append(EmptyCallback::<f32>::new(Box::new(move || {
+            let _ = ready_tx.send(());
+        })));
+
+        ready_rx.recv_timeout(timeout).is_ok()
+    }
+
+    fn play_music(&self, _music_id: MusicId, data: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        // Decode from memory (instant)
+        let source = Decoder::new(Cursor::new(data))?;
+
+        let new_sink = Sink::try_new(&self.stream_handle)?;
+
+        // Play new music on repeat
+        new_sink.append(source.repeat_infinite());
+        new_sink.play();
+
+        // Stop any existing music on both decks
+        let mut music = self.music.lock().unwrap();
+        music.swap(Some(new_sink));
+        music.set_crossfade_and_stop_outgoing(1.0);
+
+        Ok(())
+    }
+
+    fn start_crossfade(&self, _music_id: MusicId, data: Vec<u8>, start_at: Duration, looping: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let source = Decoder::new(Cursor::new(data))?;
+
+        let new_sink = Sink::try_new(&self.stream_handle)?;
+        new_sink.set_volume(0.0);
+        if looping {
+            new_sink.append(source.repeat_infinite().skip_duration(start_at));
+        } else {
+            new_sink.append(source.skip_duration(start_at));
+        }
+        new_sink.play();
+
+        self.music.lock().unwrap().swap(Some(new_sink));
+
+        Ok(())
+    }
+
+    fn start_crossfade_to_silence(&self) {
+        self.music.lock().unwrap().swap(None);
+    }
+
+    fn set_crossfade(&self, progress: f32) {
+        self.music.lock().unwrap().set_crossfade_and_stop_outgoing(progress);
+    }
+
+    fn play_sfx(&self, sfx_id: SfxId, sound: SfxSound, params: SfxParams) -> Result<(), Box<dyn std::error::Error>> {
+        let source: Box<dyn Source<Item = f32> + Send> = match sound {
+            SfxSound::Encoded(data) => Box::new(Decoder::new(Cursor::new(data))?.convert_samples()),
+            SfxSound::Synth(synth_params) => Box::new(SynthSource::new(synth_params)),
+        };
+        let source = source.speed(params.pitch);
+
+        // Place the effect in the stereo field, folding the source down to mono first
+        let downmix = 1.0 / source.channels() as f32;
+        let source = ChannelVolume::new(source, pan_gains(params.pan).iter().map(|gain| gain * downmix).collect());
+
+        let mut fx_voices = self.fx_voices.lock().unwrap();
+        fx_voices.reap(|sink| sink.empty());
+
+        let Some((slot, evicted)) = fx_voices.allocate(sfx_id, &params) else {
+            return Ok(());
+        };
+
+        // Cut off whatever the voice was playing before
+        if let Some(evicted_sink) = evicted {
+            evicted_sink.stop();
+        }
+
+        // Create new sink for this FX
+        let new_sink = Sink::try_new(&self.stream_handle)?;
+        new_sink.set_volume(params.volume.clamp(0.0, 1.0));
+        new_sink.append(source);
+        new_sink.play();
+
+        fx_voices.assign(slot, sfx_id, params.priority, new_sink);
+
+        Ok(())
+    }
+
+    fn stop_music(&self) {
+        for sink in self.music.lock().unwrap().decks.iter().flatten() {
+            sink.stop();
+        }
+    }
+
+    fn pause_music(&self) {
+        for sink in self.music.lock().unwrap().decks.iter().flatten() {
+            sink.pause();
+        }
+    }
+
+    fn resume_music(&self) {
+        for sink in self.music.lock().unwrap().decks.iter().flatten() {
+            sink.play();
+        }
+    }
+
+    fn set_music_volume(&self, volume: f32) {
+        let mut music = self.music.lock().unwrap();
+        music.volume = volume.clamp(0.0, 1.0);
+        music.apply_volumes();
+    }
+
+    fn get_music_volume(&self) -> f32 {
+        self.music.lock().unwrap().volume
+    }
+
+    fn set_music_speed(&self, speed: f32) {
+        let mut music = self.music.lock().unwrap();
+        music.speed = speed;
+        music.apply_volumes();
+    }
+
+    fn is_music_empty(&self) -> bool {
+        let music = self.music.lock().unwrap();
+        music.decks[music.active].as_ref().is_none_or(|sink| sink.empty())
+    }
+
+    fn stop_fx(&self) {
+        for sink in self.fx_voices.lock().unwrap().drain() {
+            sink.stop();
+        }
+    }
+
+    fn is_fx_playing(&self) -> bool {
+        let mut fx_voices = self.fx_voices.lock().unwrap();
+        fx_voices.reap(|sink| sink.empty());
+        fx_voices.active_count() > 0
+    }
+
+    fn is_sfx_playing(&self, sfx_id: SfxId) -> bool {
+        let mut fx_voices = self.fx_voices.lock().unwrap();
+        fx_voices.reap(|sink| sink.empty());
+        fx_voices.is_playing(sfx_id)
+    }
+}
+
+/// Something the `NullBackend` was asked to play
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioEvent {
+    MusicStarted(MusicId),
+    MusicStopped,
+    SfxPlayed(SfxId),
+}
+
+/// Shared handle to the events recorded by a `NullBackend`.
+/// Clone it before handing the backend to the `AudioManager` to inspect what was played.
+#[derive(Clone, Default)]
+pub struct AudioLog {
+    events: Arc<Mutex<Vec<AudioEvent>>>,
+    held_sfx: Arc<Mutex<Vec<SfxId>>>,
+}
+
+impl AudioLog {
+    fn record(&self, event: AudioEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    pub fn events(&self) -> Vec<AudioEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// How many times the given sound effect was triggered
+    pub fn sfx_count(&self, sfx_id: SfxId) -> usize {
+        self.events.lock().unwrap()
+            .iter()
+            .filter(|event| **event == AudioEvent::SfxPlayed(sfx_id))
+            .count()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+
+    /// Make the `NullBackend` report `sfx_id` as playing until it is released again
+    pub fn hold_sfx(&self, sfx_id: SfxId, held: bool) {
+        let mut held_sfx = self.held_sfx.lock().unwrap();
+        held_sfx.retain(|&other| other != sfx_id);
+        if held {
+            held_sfx.push(sfx_id);
+        }
+    }
+
+    fn is_held(&self, sfx_id: SfxId) -> bool {
+        self.held_sfx.lock().unwrap().contains(&sfx_id)
+    }
+}
+
+/// Silent backend used when no output device exists (CI containers, headless machines).
+/// Nothing is decoded; every request is recorded in an `AudioLog` instead.
+pub struct NullBackend {
+    log: AudioLog,
+    music_playing: Mutex<bool>,
+    music_volume: Mutex<f32>,
+}
+
+impl NullBackend {
+    pub fn new() -> Self {
+        NullBackend {
+            log: AudioLog::default(),
+            music_playing: Mutex::new(false),
+            music_volume: Mutex::new(1.0),
+        }
+    }
+
+    pub fn log(&self) -> AudioLog {
+        self.log.clone()
+    }
+}
+
+impl Default for NullBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn wait_until_ready(&self, _timeout: Duration) -> bool {
+        true
+    }
+
+    fn play_music(&self, music_id: MusicId, _data: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        *self.music_playing.lock().unwrap() = true;
+        self.log.record(AudioEvent::MusicStarted(music_id));
+        Ok(())
+    }
+
+    fn start_crossfade(&self, music_id: MusicId, _data: Vec<u8>, _start_at: Duration, _looping: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.play_music(music_id, Vec::new())
+    }
+
+    fn start_crossfade_to_silence(&self) {
+        self.stop_music();
+    }
+
+    fn set_crossfade(&self, _progress: f32) {}
+
+    fn play_sfx(&self, sfx_id: SfxId, _sound: SfxSound, _params: SfxParams) -> Result<(), Box<dyn std::error::Error>> {
+        self.log.record(AudioEvent::SfxPlayed(sfx_id));
+        Ok(())
+    }
+
+    fn stop_music(&self) {
+        *self.music_playing.lock().unwrap() = false;
+        self.log.record(AudioEvent::MusicStopped);
+    }
+
+    fn pause_music(&self) {}
+
+    fn resume_music(&self) {}
+
+    fn set_music_volume(&self, volume: f32) {
+        *self.music_volume.lock().unwrap() = volume.clamp(0.0, 1.0);
+    }
+
+    fn get_music_volume(&self) -> f32 {
+        *self.music_volume.lock().unwrap()
+    }
+
+    fn set_music_speed(&self, _speed: f32) {}
+
+    fn is_music_empty(&self) -> bool {
+        // Looping music never drains, so it is only empty when stopped
+        !*self.music_playing.lock().unwrap()
+    }
+
+    fn stop_fx(&self) {}
+
+    fn is_fx_playing(&self) -> bool {
+        // Effects "finish" the instant they are triggered, unless held through the log
+        !self.log.held_sfx.lock().unwrap().is_empty()
+    }
+
+    fn is_sfx_playing(&self, sfx_id: SfxId) -> bool {
+        self.log.is_held(sfx_id)
+    }
+}
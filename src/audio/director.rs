@@ -0,0 +1,293 @@
+use crate::audio::manager::MusicId;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Parts of the game that each have their own music
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scene {
+    Title,
+    Gameplay,
+    PerkSelect,
+    GameOver,
+}
+
+pub struct Playlist {
+    pub tracks: Vec<MusicId>,
+    pub shuffle: bool,
+}
+
+/// What the director wants the audio backend to do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MusicCommand {
+    /// Start a track on the idle deck, `start_at` into the track, and begin fading it in.
+    /// A `looping` track is the only one its playlist can play, so it repeats instead of draining.
+    CrossfadeTo { music_id: MusicId, start_at: Duration, looping: bool },
+    /// Fade the current track out without starting a new one
+    CrossfadeToSilence,
+    /// Progress of the ongoing crossfade, from 0.0 (old deck only) to 1.0 (new deck only)
+    SetCrossfade(f32),
+}
+
+/// Where a scene's playlist currently is, kept so the scene can resume where it left off
+struct PlaylistCursor {
+    order: Vec<usize>,
+    position: usize,
+    elapsed: Duration,
+}
+
+/// Decides which track plays for which scene and when to crossfade.
+/// It never touches audio itself; the `AudioManager` carries out the returned `MusicCommand`s.
+pub struct MusicDirector {
+    playlists: HashMap<Scene, Playlist>,
+    cursors: HashMap<Scene, PlaylistCursor>,
+    scene: Option<Scene>,
+    current_track: Option<MusicId>,
+    fade_elapsed: Option<Duration>,
+    crossfade_duration: Duration,
+}
+
+impl MusicDirector {
+    pub fn new(playlists: HashMap<Scene, Playlist>, crossfade_duration: Duration) -> Self {
+        MusicDirector {
+            playlists,
+            cursors: HashMap::new(),
+            scene: None,
+            current_track: None,
+            fade_elapsed: None,
+            crossfade_duration,
+        }
+    }
+
+    /// The playlists shipped with the game
+    pub fn default_playlists() -> HashMap<Scene, Playlist> {
+        HashMap::from([
+            (Scene::Title, Playlist { tracks: vec![MusicId::Music0], shuffle: false }),
+            (Scene::Gameplay, Playlist { tracks: vec![MusicId::Music0, MusicId::Music1], shuffle: false }),
+            (Scene::PerkSelect, Playlist { tracks: vec![MusicId::Music0], shuffle: false }),
+            (Scene::GameOver, Playlist { tracks: vec![], shuffle: false }),
+        ])
+    }
+
+    pub fn scene(&self) -> Option<Scene> {
+        self.scene
+    }
+
+    pub fn set_shuffle(&mut self, scene: Scene, shuffle: bool) {
+        if let Some(playlist) = self.playlists.get_mut(&scene) {
+            playlist.shuffle = shuffle;
+        }
+
+        // Rebuild the play order on next use
+        self.cursors.remove(&scene);
+    }
+
+    /// Switch to the music of `scene`, resuming its playlist at the track and position it was left at
+    pub fn set_scene(&mut self, scene: Scene, is_loaded: impl Fn(MusicId) -> bool) -> Vec<MusicCommand> {
+        if self.scene == Some(scene) {
+            return Vec::new();
+        }
+
+        self.scene = Some(scene);
+        self.start_current_track(&is_loaded)
+    }
+
+    /// Forget where a scene's playlist was, so it starts from the top next time
+    pub fn restart_scene(&mut self, scene: Scene) {
+        self.cursors.remove(&scene);
+    }
+
+    /// Advance fades and playlists by `delta`. `track_finished` tells whether the active deck has drained.
+    pub fn update(&mut self, delta: Duration, track_finished: bool, is_loaded: impl Fn(MusicId) -> bool) -> Vec<MusicCommand> {
+        let mut commands = Vec::new();
+
+        if let Some(fade_elapsed) = self.fade_elapsed.as_mut() {
+            *fade_elapsed += delta;
+            let progress = if self.crossfade_duration.is_zero() {
+                1.0
+            } else {
+                (fade_elapsed.as_secs_f32() / self.crossfade_duration.as_secs_f32()).min(1.0)
+            };
+
+            commands.push(MusicCommand::SetCrossfade(progress));
+
+            if progress >= 1.0 {
+                self.fade_elapsed = None;
+            }
+        }
+
+        let Some(scene) = self.scene else {
+            return commands;
+        };
+
+        if self.current_track.is_none() {
+            return commands;
+        }
+
+        if track_finished {
+            // Move on to the next track in the playlist
+            let track_count = self.playlists.get(&scene).map_or(0, |playlist| playlist.tracks.len());
+            let shuffle = self.playlists.get(&scene).is_some_and(|playlist| playlist.shuffle);
+            let cursor = self.cursor(scene);
+            cursor.position += 1;
+            cursor.elapsed = Duration::ZERO;
+
+            if cursor.position >= track_count {
+                cursor.position = 0;
+                if shuffle {
+                    cursor.order.shuffle(&mut rand::rng());
+                }
+            }
+
+            commands.extend(self.start_current_track(&is_loaded));
+        } else {
+            self.cursor(scene).elapsed += delta;
+        }
+
+        commands
+    }
+
+    fn start_current_track(&mut self, is_loaded: &impl Fn(MusicId) -> bool) -> Vec<MusicCommand> {
+        let Some(scene) = self.scene else {
+            return Vec::new();
+        };
+
+        let tracks = self.playlists.get(&scene).map(|playlist| playlist.tracks.clone()).unwrap_or_default();
+        let looping = tracks.iter().filter(|&&music_id| is_loaded(music_id)).count() == 1;
+        let cursor = self.cursor(scene);
+
+        // Skip over tracks that failed to load, giving up after one lap of the playlist
+        let mut next_track = None;
+        for _ in 0..cursor.order.len() {
+            let music_id = tracks[cursor.order[cursor.position]];
+            if is_loaded(music_id) {
+                next_track = Some((music_id, cursor.elapsed));
+                break;
+            }
+
+            cursor.position = (cursor.position + 1) % cursor.order.len();
+            cursor.elapsed = Duration::ZERO;
+        }
+
+        self.fade_elapsed = Some(Duration::ZERO);
+
+        match next_track {
+            Some((music_id, start_at)) => {
+                self.current_track = Some(music_id);
+                vec![MusicCommand::CrossfadeTo { music_id, start_at, looping }]
+            }
+            None => {
+                self.current_track = None;
+                vec![MusicCommand::CrossfadeToSilence]
+            }
+        }
+    }
+
+    fn cursor(&mut self, scene: Scene) -> &mut PlaylistCursor {
+        let playlists = &self.playlists;
+
+        self.cursors.entry(scene).or_insert_with(|| {
+            let (track_count, shuffle) = playlists
+                .get(&scene)
+                .map_or((0, false), |playlist| (playlist.tracks.len(), playlist.shuffle));
+
+            let mut order: Vec<usize> = (0..track_count).collect();
+            if shuffle {
+                order.shuffle(&mut rand::rng());
+            }
+
+            PlaylistCursor { order, position: 0, elapsed: Duration::ZERO }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn director(playlists: impl IntoIterator<Item = (Scene, Vec<MusicId>, bool)>) -> MusicDirector {
+        let playlists = playlists
+            .into_iter()
+            .map(|(scene, tracks, shuffle)| (scene, Playlist { tracks, shuffle }))
+            .collect();
+        MusicDirector::new(playlists, Duration::from_secs(1))
+    }
+
+    fn started_tracks(commands: &[MusicCommand]) -> Vec<MusicId> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                MusicCommand::CrossfadeTo { music_id, .. } => Some(*music_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_scene_resumes_where_it_was_left() {
+        let mut director = director([
+            (Scene::Gameplay, vec![MusicId::Music0, MusicId::Music1], false),
+            (Scene::Title, vec![MusicId::Music0], false),
+        ]);
+
+        director.set_scene(Scene::Gameplay, |_| true);
+        director.update(Duration::from_secs(5), true, |_| true);
+        director.update(Duration::from_secs(2), false, |_| true);
+        director.set_scene(Scene::Title, |_| true);
+
+        let commands = director.set_scene(Scene::Gameplay, |_| true);
+        assert_eq!(
+            commands,
+            vec![MusicCommand::CrossfadeTo { music_id: MusicId::Music1, start_at: Duration::from_secs(2), looping: false }]
+        );
+
+        // Unless it was restarted in the meantime
+        director.set_scene(Scene::Title, |_| true);
+        director.restart_scene(Scene::Gameplay);
+        assert_eq!(started_tracks(&director.set_scene(Scene::Gameplay, |_| true)), vec![MusicId::Music0]);
+    }
+
+    #[test]
+    fn tracks_that_failed_to_load_are_skipped() {
+        let mut director = director([
+            (Scene::Gameplay, vec![MusicId::Music1, MusicId::Music0], false),
+            (Scene::GameOver, vec![MusicId::Music1], false),
+        ]);
+        let is_loaded = |music_id| music_id == MusicId::Music0;
+
+        // The only track left loops rather than draining into itself
+        assert_eq!(
+            director.set_scene(Scene::Gameplay, is_loaded),
+            vec![MusicCommand::CrossfadeTo { music_id: MusicId::Music0, start_at: Duration::ZERO, looping: true }]
+        );
+        assert_eq!(director.set_scene(Scene::GameOver, is_loaded), vec![MusicCommand::CrossfadeToSilence]);
+    }
+
+    #[test]
+    fn a_shuffled_playlist_plays_every_track_once_per_lap() {
+        let mut director = director([(Scene::Gameplay, vec![MusicId::Music0, MusicId::Music1], true)]);
+
+        for _ in 0..10 {
+            director.restart_scene(Scene::Gameplay);
+            director.set_scene(Scene::Title, |_| true);
+
+            let mut played = started_tracks(&director.set_scene(Scene::Gameplay, |_| true));
+            played.extend(started_tracks(&director.update(Duration::from_secs(1), true, |_| true)));
+
+            assert_eq!(played.len(), 2);
+            assert!(played.contains(&MusicId::Music0) && played.contains(&MusicId::Music1), "{:?}", played);
+        }
+    }
+
+    #[test]
+    fn switching_shuffle_off_plays_the_playlist_in_order() {
+        let mut director = director([(Scene::Gameplay, vec![MusicId::Music0, MusicId::Music1], true)]);
+        director.set_shuffle(Scene::Gameplay, false);
+
+        let mut played = started_tracks(&director.set_scene(Scene::Gameplay, |_| true));
+        played.extend(started_tracks(&director.update(Duration::from_secs(1), true, |_| true)));
+        played.extend(started_tracks(&director.update(Duration::from_secs(1), true, |_| true)));
+
+        assert_eq!(played, vec![MusicId::Music0, MusicId::Music1, MusicId::Music0]);
+    }
+}
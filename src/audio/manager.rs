@@ -1,59 +1,149 @@
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use crate::audio::backend::{AudioBackend, NullBackend, RodioBackend};
+use crate::audio::director::{MusicCommand, MusicDirector, Scene};
+use crate::audio::mixer::{sfx_settings, SfxParams};
+use crate::audio::synth::{parse_synth_definitions, SynthParams};
+use rand::Rng;
+use crate::state::constants::audio::{
+    AUDIO_READY_TIMEOUT_MS, CURSED_PERK_CHOSEN_FILE, GAME_OVER_FILE, HUNGRY_WORM_PERK_CHOSEN_FILE, LUCKY_PERK_CHOSEN_FILE, MUSIC_0_FILE,
+    MUSIC_1_FILE, MUSIC_CROSSFADE_MS, MUSIC_DUCK_FADE_MS, MUSIC_DUCK_LEVEL, MUSIC_DUCK_RELEASE_MS, MUSIC_INTENSITY_RESPONSE_MS,
+    MUSIC_MAX_SPEEDUP, NEED_FOR_SPEED_PERK_CHOSEN_FILE, NEW_PERK_FILE, POWER_UP_PERK_CHOSEN_FILE, SFX_DEFINITIONS_FILE, SFX_PAN_WIDTH,
+    SLOW_DOWN_PERK_CHOSEN_FILE, SNAKE_EAT_FOOD_FILE,
+};
+use crate::state::constants::graphics::ART_WIDTH;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::Cursor;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MusicId {
     Music0,
+    Music1,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SfxId {
     NewPerk,
-    Eat
+    Eat,
+    PerkNeedForSpeed,
+    PerkHungryWorm,
+    PerkSlowDown,
+    PerkLucky,
+    PerkPowerUp,
+    PerkCursed,
+    GameOver,
+}
+
+impl SfxId {
+    /// Section name used for this effect in the synth definitions file
+    pub fn name(&self) -> &'static str {
+        match self {
+            SfxId::NewPerk => "new_perk",
+            SfxId::Eat => "eat",
+            SfxId::PerkNeedForSpeed => "need_for_speed",
+            SfxId::PerkHungryWorm => "hungry_worm",
+            SfxId::PerkSlowDown => "slow_down",
+            SfxId::PerkLucky => "lucky",
+            SfxId::PerkPowerUp => "power_up",
+            SfxId::PerkCursed => "cursed",
+            SfxId::GameOver => "game_over",
+        }
+    }
+}
+
+/// A preloaded sound effect, either an encoded audio file or parameters for the built-in synthesizer
+#[derive(Debug, Clone)]
+pub enum SfxSound {
+    Encoded(Vec<u8>),
+    Synth(SynthParams),
 }
 
 pub struct AudioManager {
-    _stream: OutputStream,
-    stream_handle: OutputStreamHandle,
-    music_sink: Arc<Mutex<Sink>>,
-    fx_sink: Arc<Mutex<Option<Sink>>>,
+    backend: Box<dyn AudioBackend>,
     // State tracking to avoid expensive checks
     music_playing: Arc<Mutex<bool>>,
     last_music_check: Arc<Mutex<Instant>>,
     music_check_interval: Duration,
     // Preloaded audio data by ID
     music_cache: Arc<Mutex<HashMap<MusicId, Vec<u8>>>>,
-    sfx_cache: Arc<Mutex<HashMap<SfxId, Vec<u8>>>>,
+    sfx_cache: Arc<Mutex<HashMap<SfxId, SfxSound>>>,
     current_music_id: Arc<Mutex<Option<MusicId>>>,
+    // Scene based playlists and crossfades
+    director: Arc<Mutex<MusicDirector>>,
+    last_director_update: Arc<Mutex<Instant>>,
+    // Gameplay intensity and the music speed that smoothly follows it
+    intensity: Arc<Mutex<f32>>,
+    music_speed: Arc<Mutex<f32>>,
+    // Disabled for mono setups, where panned effects would only come out of one speaker
+    stereo_panning: Arc<Mutex<bool>>,
+    // Music volume as set by the player, and the ducking applied on top of it
+    music_volume: Arc<Mutex<f32>>,
+    duck: Arc<Mutex<MusicDuck>>,
+    // Held back while a rolled-back stretch of an online game plays again, so nothing is heard twice
+    sfx_muted: Arc<Mutex<bool>>,
+    // Headless games play nothing and have nothing preloaded
+    silent: bool,
+}
+
+/// Lowers the music while an important sound effect plays
+struct MusicDuck {
+    gain: f32,
+    while_playing: Option<SfxId>,
+}
+
+impl Default for AudioManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AudioManager {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        // Get the default audio device with full control
-        let (_stream, stream_handle) = OutputStream::try_default()?;
+    /// Use the default output device, falling back to silent playback if there is none.
+    /// Returns once the output stream is confirmed to be running, so playback can start right away.
+    pub fn new() -> Self {
+        let backend: Box<dyn AudioBackend> = match RodioBackend::new() {
+            Ok(backend) => Box::new(backend),
+            Err(e) => {
+                eprintln!("Warning: No audio output device available ({}), continuing without sound", e);
+                Box::new(NullBackend::new())
+            }
+        };
 
-        // Create dedicated sink for background music
-        let music_sink = Arc::new(Mutex::new(Sink::try_new(&stream_handle)?));
+        if !backend.wait_until_ready(Duration::from_millis(AUDIO_READY_TIMEOUT_MS)) {
+            eprintln!("Warning: Audio output did not become ready within {} ms", AUDIO_READY_TIMEOUT_MS);
+        }
 
-        // FX sink will be created on-demand
-        let fx_sink = Arc::new(Mutex::new(None));
+        Self::with_backend(backend)
+    }
 
-        Ok(AudioManager {
-            _stream,
-            stream_handle,
-            music_sink,
-            fx_sink,
+    /// Create a manager on top of an explicit backend, e.g. a `NullBackend` whose log is inspected in tests
+    pub fn with_backend(backend: Box<dyn AudioBackend>) -> Self {
+        AudioManager {
+            backend,
             music_playing: Arc::new(Mutex::new(false)),
             last_music_check: Arc::new(Mutex::new(Instant::now())),
             music_check_interval: Duration::from_millis(500),
             music_cache: Arc::new(Mutex::new(HashMap::new())),
             sfx_cache: Arc::new(Mutex::new(HashMap::new())),
             current_music_id: Arc::new(Mutex::new(None)),
-        })
+            director: Arc::new(Mutex::new(MusicDirector::new(
+                MusicDirector::default_playlists(),
+                Duration::from_millis(MUSIC_CROSSFADE_MS),
+            ))),
+            last_director_update: Arc::new(Mutex::new(Instant::now())),
+            intensity: Arc::new(Mutex::new(0.0)),
+            music_speed: Arc::new(Mutex::new(1.0)),
+            stereo_panning: Arc::new(Mutex::new(true)),
+            music_volume: Arc::new(Mutex::new(1.0)),
+            duck: Arc::new(Mutex::new(MusicDuck { gain: 1.0, while_playing: None })),
+            sfx_muted: Arc::new(Mutex::new(false)),
+            silent: false,
+        }
+    }
+
+    /// A manager for headless games: no output device, nothing to preload, and every sound effect quietly skipped
+    pub fn silent() -> Self {
+        AudioManager { silent: true, ..Self::with_backend(Box::new(NullBackend::new())) }
     }
 
     /// Load all music files at startup - call this once during initialization
@@ -62,7 +152,8 @@ impl AudioManager {
 
         // Define your music file mappings here
         let music_files = [
-            (MusicId::Music0, "assets/audio/music_0.mp3")
+            (MusicId::Music0, MUSIC_0_FILE),
+            (MusicId::Music1, MUSIC_1_FILE),
         ];
 
         let mut cache = self.music_cache.lock().unwrap();
@@ -87,16 +178,36 @@ impl AudioManager {
         Ok(())
     }
 
-    /// Load all sound effects at startup - call this once during initialization
+    /// Load all sound effects at startup - call this once during initialization.
+    /// Effects without an audio file are synthesized from the definitions file instead.
     pub fn preload_all_sfx(&self) -> Result<(), Box<dyn std::error::Error>> {
         println!("Preloading all sound effects...");
 
         // Define your SFX file mappings here
         let sfx_files = [
-            (SfxId::NewPerk, "assets/audio/new_perk.mp3"),
-            (SfxId::Eat, "assets/audio/eat.mp3"),
+            (SfxId::NewPerk, NEW_PERK_FILE),
+            (SfxId::Eat, SNAKE_EAT_FOOD_FILE),
+            (SfxId::PerkNeedForSpeed, NEED_FOR_SPEED_PERK_CHOSEN_FILE),
+            (SfxId::PerkHungryWorm, HUNGRY_WORM_PERK_CHOSEN_FILE),
+            (SfxId::PerkSlowDown, SLOW_DOWN_PERK_CHOSEN_FILE),
+            (SfxId::PerkLucky, LUCKY_PERK_CHOSEN_FILE),
+            (SfxId::PerkPowerUp, POWER_UP_PERK_CHOSEN_FILE),
+            (SfxId::PerkCursed, CURSED_PERK_CHOSEN_FILE),
+            (SfxId::GameOver, GAME_OVER_FILE),
         ];
 
+        // A broken definitions file only costs the synthesized effects, never the game
+        let synth_definitions = match std::fs::read_to_string(SFX_DEFINITIONS_FILE) {
+            Ok(text) => parse_synth_definitions(&text).unwrap_or_else(|e| {
+                eprintln!("Warning: Invalid synth definitions in {}: {}", SFX_DEFINITIONS_FILE, e);
+                HashMap::new()
+            }),
+            Err(e) => {
+                eprintln!("Warning: Failed to load synth definitions from {}: {}", SFX_DEFINITIONS_FILE, e);
+                HashMap::new()
+            }
+        };
+
         let mut cache = self.sfx_cache.lock().unwrap();
         let mut total_size = 0;
 
@@ -106,12 +217,18 @@ impl AudioManager {
                     let mut file_data = Vec::new();
                     std::io::Read::read_to_end(&mut file, &mut file_data)?;
                     total_size += file_data.len();
-                    cache.insert(*sfx_id, file_data.clone());
                     println!("Loaded {:?}: {} ({} KB)", sfx_id, file_path, file_data.len() / 1024);
+                    cache.insert(*sfx_id, SfxSound::Encoded(file_data));
                 }
-                Err(e) => {
-                    eprintln!("Warning: Failed to load {:?} from {}: {}", sfx_id, file_path, e);
-                }
+                Err(e) => match synth_definitions.get(sfx_id.name()) {
+                    Some(params) => {
+                        println!("Synthesizing {:?} from [{}] in {}", sfx_id, sfx_id.name(), SFX_DEFINITIONS_FILE);
+                        cache.insert(*sfx_id, SfxSound::Synth(*params));
+                    }
+                    None => {
+                        eprintln!("Warning: Failed to load {:?} from {}: {}", sfx_id, file_path, e);
+                    }
+                },
             }
         }
 
@@ -146,18 +263,7 @@ impl AudioManager {
             }
         };
 
-        // Decode from memory (instant)
-        let cursor = Cursor::new(audio_data);
-        let source = Decoder::new(cursor)?;
-
-        let music_sink = self.music_sink.lock().unwrap();
-
-        // Stop any existing music
-        music_sink.stop();
-
-        // Play new music on repeat
-        music_sink.append(source.repeat_infinite());
-        music_sink.play();
+        self.backend.play_music(music_id, audio_data)?;
 
         // Update our state tracking
         *self.music_playing.lock().unwrap() = true;
@@ -166,68 +272,197 @@ impl AudioManager {
         Ok(())
     }
 
-    /// Play sound effect by ID (ultra-fast, no file I/O)
-    pub fn play_sfx(&self, sfx_id: SfxId) -> Result<(), Box<dyn std::error::Error>> {
-        // Get preloaded audio data
-        let audio_data = {
-            let cache = self.sfx_cache.lock().unwrap();
-            match cache.get(&sfx_id) {
-                Some(data) => data.clone(),
-                None => {
-                    return Err(format!("SFX {:?} not found in cache! Did you call preload_all_sfx()?", sfx_id).into());
-                }
-            }
+    /// Switch to the playlist of `scene`, crossfading from whatever plays now.
+    /// A scene that was left earlier resumes at the track and position it was at.
+    pub fn set_scene(&self, scene: Scene) {
+        let commands = {
+            let cache = self.music_cache.lock().unwrap();
+            self.director.lock().unwrap().set_scene(scene, |music_id| cache.contains_key(&music_id))
         };
 
-        let cursor = Cursor::new(audio_data);
-        let source = Decoder::new(cursor)?;
+        self.execute_music_commands(commands);
+    }
+
+    /// Start the playlist of `scene` from the top the next time it is entered
+    pub fn restart_scene(&self, scene: Scene) {
+        self.director.lock().unwrap().restart_scene(scene);
+    }
 
-        let mut fx_sink_guard = self.fx_sink.lock().unwrap();
+    pub fn set_shuffle(&self, scene: Scene, shuffle: bool) {
+        self.director.lock().unwrap().set_shuffle(scene, shuffle);
+    }
+
+    pub fn get_scene(&self) -> Option<Scene> {
+        self.director.lock().unwrap().scene()
+    }
 
-        // Stop and replace any existing FX
-        if let Some(existing_sink) = fx_sink_guard.as_ref() {
-            existing_sink.stop();
+    /// Advance crossfades and playlists - call this once per frame
+    pub fn update(&self) {
+        let now = Instant::now();
+        let delta = {
+            let mut last_update = self.last_director_update.lock().unwrap();
+            let delta = now.duration_since(*last_update);
+            *last_update = now;
+            delta
+        };
+
+        self.update_music_speed(delta);
+        self.update_music_duck(delta);
+
+        let track_finished = self.backend.is_music_empty();
+        let commands = {
+            let cache = self.music_cache.lock().unwrap();
+            self.director.lock().unwrap().update(delta, track_finished, |music_id| cache.contains_key(&music_id))
+        };
+
+        self.execute_music_commands(commands);
+    }
+
+    /// Duck the music while `sfx_id` plays, restoring it smoothly once the effect has finished
+    pub fn duck_music_while(&self, sfx_id: SfxId) {
+        self.duck.lock().unwrap().while_playing = Some(sfx_id);
+    }
+
+    fn update_music_duck(&self, delta: Duration) {
+        let mut duck = self.duck.lock().unwrap();
+
+        if duck.while_playing.is_some_and(|sfx_id| !self.backend.is_sfx_playing(sfx_id)) {
+            duck.while_playing = None;
         }
 
-        // Create new sink for this FX
-        let new_sink = Sink::try_new(&self.stream_handle)?;
-        new_sink.append(source);
-        new_sink.play();
+        // Move towards the target at a fixed rate, dipping quickly and recovering a little slower
+        let (target, fade_ms) = match duck.while_playing {
+            Some(_) => (MUSIC_DUCK_LEVEL, MUSIC_DUCK_FADE_MS),
+            None => (1.0, MUSIC_DUCK_RELEASE_MS),
+        };
+        let step = (1.0 - MUSIC_DUCK_LEVEL) * delta.as_secs_f32() * 1000.0 / fade_ms as f32;
+        let gain = if duck.gain > target {
+            (duck.gain - step).max(target)
+        } else {
+            (duck.gain + step).min(target)
+        };
 
-        *fx_sink_guard = Some(new_sink);
+        if gain != duck.gain {
+            duck.gain = gain;
+            self.backend.set_music_volume(*self.music_volume.lock().unwrap() * gain);
+        }
+    }
 
-        Ok(())
+    pub fn set_stereo_panning(&self, enabled: bool) {
+        *self.stereo_panning.lock().unwrap() = enabled;
+    }
+
+    pub fn is_stereo_panning(&self) -> bool {
+        *self.stereo_panning.lock().unwrap()
+    }
+
+    /// How intense gameplay currently is, from 0.0 (calm) to 1.0 (frantic).
+    /// The gameplay music speeds up with it; other scenes play at normal speed.
+    pub fn set_intensity(&self, intensity: f32) {
+        *self.intensity.lock().unwrap() = intensity.clamp(0.0, 1.0);
+    }
+
+    pub fn get_intensity(&self) -> f32 {
+        *self.intensity.lock().unwrap()
+    }
+
+    fn update_music_speed(&self, delta: Duration) {
+        let target = if self.get_scene() == Some(Scene::Gameplay) {
+            1.0 + self.get_intensity() * MUSIC_MAX_SPEEDUP
+        } else {
+            1.0
+        };
+
+        // Ease towards the target so tempo changes are gradual rather than jumping every tick
+        let mut music_speed = self.music_speed.lock().unwrap();
+        let response = (delta.as_secs_f32() * 1000.0 / MUSIC_INTENSITY_RESPONSE_MS as f32).min(1.0);
+        let speed = *music_speed + (target - *music_speed) * response;
+
+        if (speed - *music_speed).abs() > f32::EPSILON {
+            *music_speed = speed;
+            self.backend.set_music_speed(speed);
+        }
+    }
+
+    fn execute_music_commands(&self, commands: Vec<MusicCommand>) {
+        for command in commands {
+            match command {
+                MusicCommand::CrossfadeTo { music_id, start_at, looping } => {
+                    let audio_data = self.music_cache.lock().unwrap().get(&music_id).cloned();
+                    let started = audio_data
+                        .map(|data| self.backend.start_crossfade(music_id, data, start_at, looping))
+                        .transpose();
+
+                    match started {
+                        Ok(Some(())) => {
+                            *self.music_playing.lock().unwrap() = true;
+                            *self.current_music_id.lock().unwrap() = Some(music_id);
+                        }
+                        Ok(None) => {}
+                        Err(e) => eprintln!("Warning: Failed to start music {:?}: {}", music_id, e),
+                    }
+                }
+                MusicCommand::CrossfadeToSilence => {
+                    self.backend.start_crossfade_to_silence();
+                    *self.music_playing.lock().unwrap() = false;
+                    *self.current_music_id.lock().unwrap() = None;
+                }
+                MusicCommand::SetCrossfade(progress) => self.backend.set_crossfade(progress),
+            }
+        }
+    }
+
+    /// Skip every sound effect until unmuted again, leaving the music playing
+    pub fn set_sfx_muted(&self, muted: bool) {
+        *self.sfx_muted.lock().unwrap() = muted;
+    }
+
+    /// Play sound effect by ID (ultra-fast, no file I/O).
+    /// `position_x` is where on screen the sound happens, used to pan it; `None` plays it centered.
+    pub fn play_sfx(&self, sfx_id: SfxId, position_x: Option<f32>) -> Result<(), Box<dyn std::error::Error>> {
+        self.play_sfx_with_volume(sfx_id, position_x, 1.0)
     }
 
     /// Play SFX with custom volume
-    pub fn play_sfx_with_volume(&self, sfx_id: SfxId, volume: f32) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn play_sfx_with_volume(&self, sfx_id: SfxId, position_x: Option<f32>, volume: f32) -> Result<(), Box<dyn std::error::Error>> {
+        if self.silent || *self.sfx_muted.lock().unwrap() {
+            return Ok(());
+        }
+
+        // Get preloaded audio data
         let audio_data = {
             let cache = self.sfx_cache.lock().unwrap();
             match cache.get(&sfx_id) {
                 Some(data) => data.clone(),
                 None => {
-                    return Err(format!("SFX {:?} not found in cache!", sfx_id).into());
+                    return Err(format!("SFX {:?} not found in cache! Did you call preload_all_sfx()?", sfx_id).into());
                 }
             }
         };
 
-        let cursor = Cursor::new(audio_data);
-        let source = Decoder::new(cursor)?;
-
-        let mut fx_sink_guard = self.fx_sink.lock().unwrap();
-
-        if let Some(existing_sink) = fx_sink_guard.as_ref() {
-            existing_sink.stop();
-        }
+        // Vary the pitch of repeated sounds slightly so they don't sound mechanical
+        let settings = sfx_settings(sfx_id);
+        let pitch = if settings.pitch_variation > 0.0 {
+            1.0 + rand::rng().random_range(-settings.pitch_variation..=settings.pitch_variation)
+        } else {
+            1.0
+        };
 
-        let new_sink = Sink::try_new(&self.stream_handle)?;
-        new_sink.set_volume(volume.clamp(0.0, 1.0));
-        new_sink.append(source);
-        new_sink.play();
+        // Map the x-coordinate across the art width onto the stereo field
+        let pan = match position_x {
+            Some(x) if self.is_stereo_panning() => ((x / ART_WIDTH as f32) * 2.0 - 1.0).clamp(-1.0, 1.0) * SFX_PAN_WIDTH,
+            _ => 0.0,
+        };
 
-        *fx_sink_guard = Some(new_sink);
+        let params = SfxParams {
+            volume,
+            pitch,
+            pan,
+            priority: settings.priority,
+            max_instances: settings.max_instances,
+        };
 
-        Ok(())
+        self.backend.play_sfx(sfx_id, audio_data, params)
     }
 
     /// Get currently playing music ID
@@ -253,7 +488,10 @@ impl AudioManager {
 
         let sfx_size: usize = self.sfx_cache.lock().unwrap()
             .values()
-            .map(|data| data.len())
+            .map(|sound| match sound {
+                SfxSound::Encoded(data) => data.len(),
+                SfxSound::Synth(_) => 0,
+            })
             .sum();
 
         (music_size, sfx_size)
@@ -272,41 +510,31 @@ impl AudioManager {
     // === ORIGINAL METHODS (unchanged) ===
 
     pub fn stop_music(&self) {
-        let music_sink = self.music_sink.lock().unwrap();
-        music_sink.stop();
+        self.backend.stop_music();
         *self.music_playing.lock().unwrap() = false;
         *self.current_music_id.lock().unwrap() = None;
     }
 
     pub fn pause_music(&self) {
-        let music_sink = self.music_sink.lock().unwrap();
-        music_sink.pause();
+        self.backend.pause_music();
     }
 
     pub fn resume_music(&self) {
-        let music_sink = self.music_sink.lock().unwrap();
-        music_sink.play();
+        self.backend.resume_music();
     }
 
     pub fn set_music_volume(&self, volume: f32) {
-        let music_sink = self.music_sink.lock().unwrap();
-        music_sink.set_volume(volume.clamp(0.0, 1.0));
+        let volume = volume.clamp(0.0, 1.0);
+        *self.music_volume.lock().unwrap() = volume;
+        self.backend.set_music_volume(volume * self.duck.lock().unwrap().gain);
     }
 
     pub fn stop_fx(&self) {
-        let mut fx_sink_guard = self.fx_sink.lock().unwrap();
-        if let Some(sink) = fx_sink_guard.as_ref() {
-            sink.stop();
-        }
-        *fx_sink_guard = None;
+        self.backend.stop_fx();
     }
 
     pub fn is_fx_playing(&self) -> bool {
-        let fx_sink_guard = self.fx_sink.lock().unwrap();
-        match fx_sink_guard.as_ref() {
-            Some(sink) => !sink.empty(),
-            None => false,
-        }
+        self.backend.is_fx_playing()
     }
 
     pub fn is_music_playing(&self) -> bool {
@@ -314,8 +542,7 @@ impl AudioManager {
         let mut last_check = self.last_music_check.lock().unwrap();
 
         if now.duration_since(*last_check) >= self.music_check_interval {
-            let music_sink = self.music_sink.lock().unwrap();
-            let actually_playing = !music_sink.empty();
+            let actually_playing = !self.backend.is_music_empty();
             *self.music_playing.lock().unwrap() = actually_playing;
             *last_check = now;
             actually_playing
@@ -329,8 +556,7 @@ impl AudioManager {
     }
 
     pub fn check_music_state_now(&self) -> bool {
-        let music_sink = self.music_sink.lock().unwrap();
-        let actually_playing = !music_sink.empty();
+        let actually_playing = !self.backend.is_music_empty();
         *self.music_playing.lock().unwrap() = actually_playing;
         *self.last_music_check.lock().unwrap() = Instant::now();
         actually_playing
@@ -340,8 +566,60 @@ impl AudioManager {
         self.music_check_interval = interval;
     }
 
+    /// Music volume as set with `set_music_volume`, regardless of any ducking in progress
     pub fn get_music_volume(&self) -> f32 {
-        let music_sink = self.music_sink.lock().unwrap();
-        music_sink.volume()
+        *self.music_volume.lock().unwrap()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_music_volume(audio_manager: &AudioManager, expected: f32) {
+        let volume = audio_manager.backend.get_music_volume();
+        assert!((volume - expected).abs() < 1e-4, "music volume is {}, expected {}", volume, expected);
+    }
+
+    #[test]
+    fn music_dips_under_an_effect_and_recovers_once_it_is_over() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let audio_manager = AudioManager::with_backend(Box::new(backend));
+        audio_manager.set_music_volume(0.8);
+
+        log.hold_sfx(SfxId::NewPerk, true);
+        audio_manager.duck_music_while(SfxId::NewPerk);
+
+        // Halfway down, then at the duck level for as long as the effect plays
+        audio_manager.update_music_duck(Duration::from_millis(MUSIC_DUCK_FADE_MS / 2));
+        assert_music_volume(&audio_manager, 0.8 * (1.0 + MUSIC_DUCK_LEVEL) / 2.0);
+        audio_manager.update_music_duck(Duration::from_millis(MUSIC_DUCK_FADE_MS));
+        assert_music_volume(&audio_manager, 0.8 * MUSIC_DUCK_LEVEL);
+        audio_manager.update_music_duck(Duration::from_secs(5));
+        assert_music_volume(&audio_manager, 0.8 * MUSIC_DUCK_LEVEL);
+
+        // Back up more slowly than it went down, without overshooting the player's volume
+        log.hold_sfx(SfxId::NewPerk, false);
+        audio_manager.update_music_duck(Duration::from_millis(MUSIC_DUCK_RELEASE_MS / 2));
+        assert_music_volume(&audio_manager, 0.8 * (1.0 + MUSIC_DUCK_LEVEL) / 2.0);
+        audio_manager.update_music_duck(Duration::from_millis(MUSIC_DUCK_RELEASE_MS));
+        assert_music_volume(&audio_manager, 0.8);
+        assert_eq!(audio_manager.get_music_volume(), 0.8);
+    }
+
+    #[test]
+    fn changing_the_volume_while_ducked_keeps_the_duck() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let audio_manager = AudioManager::with_backend(Box::new(backend));
+
+        log.hold_sfx(SfxId::GameOver, true);
+        audio_manager.duck_music_while(SfxId::GameOver);
+        audio_manager.update_music_duck(Duration::from_millis(MUSIC_DUCK_FADE_MS));
+        audio_manager.set_music_volume(0.5);
+
+        assert_music_volume(&audio_manager, 0.5 * MUSIC_DUCK_LEVEL);
+        assert_eq!(audio_manager.get_music_volume(), 0.5);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::audio::manager::SfxId;
+
+/// How a sound effect competes for voices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SfxSettings {
+    /// Higher priority sounds may steal voices from lower priority ones when the pool is full
+    pub priority: u8,
+    /// How many instances of this sound may overlap before the oldest one is cut off
+    pub max_instances: usize,
+    /// Random pitch offset applied per play, e.g. 0.08 means anywhere between 92% and 108%
+    pub pitch_variation: f32,
+}
+
+pub fn sfx_settings(sfx_id: SfxId) -> SfxSettings {
+    match sfx_id {
+        SfxId::NewPerk => SfxSettings { priority: 3, max_instances: 1, pitch_variation: 0.0 },
+        SfxId::Eat => SfxSettings { priority: 1, max_instances: 4, pitch_variation: 0.08 },
+        SfxId::PerkNeedForSpeed
+        | SfxId::PerkHungryWorm
+        | SfxId::PerkSlowDown
+        | SfxId::PerkLucky
+        | SfxId::PerkPowerUp
+        | SfxId::PerkCursed => SfxSettings { priority: 2, max_instances: 1, pitch_variation: 0.0 },
+        SfxId::GameOver => SfxSettings { priority: 4, max_instances: 1, pitch_variation: 0.0 },
+    }
+}
+
+/// Everything a backend needs to know to play a single sound effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SfxParams {
+    pub volume: f32,
+    pub pitch: f32,
+    /// Stereo position from -1.0 (left) to 1.0 (right), 0.0 plays centered
+    pub pan: f32,
+    pub priority: u8,
+    pub max_instances: usize,
+}
+
+/// Left and right channel gains for `pan`, using equal power panning scaled so a centered sound plays at full volume
+pub fn pan_gains(pan: f32) -> [f32; 2] {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+    [angle.cos() * std::f32::consts::SQRT_2, angle.sin() * std::f32::consts::SQRT_2]
+}
+
+struct Voice<V> {
+    sfx_id: SfxId,
+    priority: u8,
+    sequence: u64,
+    handle: V,
+}
+
+/// A fixed number of voices that sound effects are allocated to.
+/// `V` is whatever the backend uses to play a single sound, e.g. a rodio `Sink`.
+pub struct VoicePool<V> {
+    voices: Vec<Option<Voice<V>>>,
+    next_sequence: u64,
+}
+
+impl<V> VoicePool<V> {
+    pub fn new(size: usize) -> Self {
+        VoicePool {
+            voices: (0..size).map(|_| None).collect(),
+            next_sequence: 0,
+        }
+    }
+
+    /// Release voices whose sound has finished playing
+    pub fn reap(&mut self, is_finished: impl Fn(&V) -> bool) {
+        for slot in self.voices.iter_mut() {
+            if slot.as_ref().is_some_and(|voice| is_finished(&voice.handle)) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Find a voice for a new instance of `sfx_id`, evicting one if needed.
+    /// Returns the chosen slot together with the handle that was evicted from it (if any),
+    /// or `None` if every voice is busy with something more important.
+    pub fn allocate(&mut self, sfx_id: SfxId, params: &SfxParams) -> Option<(usize, Option<V>)> {
+        // The same sound over its instance limit replaces its own oldest instance
+        let instances = self.voices.iter().flatten().filter(|voice| voice.sfx_id == sfx_id).count();
+        if instances >= params.max_instances.max(1) {
+            let slot = self.oldest_slot(|voice| voice.sfx_id == sfx_id)?;
+            return Some((slot, self.voices[slot].take().map(|voice| voice.handle)));
+        }
+
+        if let Some(slot) = self.voices.iter().position(|voice| voice.is_none()) {
+            return Some((slot, None));
+        }
+
+        // Pool is full, so steal the oldest voice among those with the lowest priority not above ours
+        let lowest_priority = self.voices.iter().flatten().map(|voice| voice.priority).min()?;
+        if lowest_priority > params.priority {
+            return None;
+        }
+
+        let slot = self.oldest_slot(|voice| voice.priority == lowest_priority)?;
+        Some((slot, self.voices[slot].take().map(|voice| voice.handle)))
+    }
+
+    /// Occupy `slot` (as returned by `allocate`) with a playing sound
+    pub fn assign(&mut self, slot: usize, sfx_id: SfxId, priority: u8, handle: V) {
+        self.voices[slot] = Some(Voice {
+            sfx_id,
+            priority,
+            sequence: self.next_sequence,
+            handle,
+        });
+        self.next_sequence += 1;
+    }
+
+    pub fn is_playing(&self, sfx_id: SfxId) -> bool {
+        self.voices.iter().flatten().any(|voice| voice.sfx_id == sfx_id)
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.voices.iter().flatten().count()
+    }
+
+    /// Empty the pool, handing back every handle so the caller can stop it
+    pub fn drain(&mut self) -> Vec<V> {
+        self.voices.iter_mut().filter_map(|slot| slot.take()).map(|voice| voice.handle).collect()
+    }
+
+    fn oldest_slot(&self, matches: impl Fn(&Voice<V>) -> bool) -> Option<usize> {
+        self.voices
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, voice)| voice.as_ref().filter(|voice| matches(voice)).map(|voice| (slot, voice.sequence)))
+            .min_by_key(|(_, sequence)| *sequence)
+            .map(|(slot, _)| slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(sfx_id: SfxId) -> SfxParams {
+        let settings = sfx_settings(sfx_id);
+        SfxParams { volume: 1.0, pitch: 1.0, pan: 0.0, priority: settings.priority, max_instances: settings.max_instances }
+    }
+
+    /// Plays `sfx_id` on the pool the way a backend would, with the handle naming the play
+    fn play(pool: &mut VoicePool<&'static str>, sfx_id: SfxId, handle: &'static str) -> Option<Option<&'static str>> {
+        let params = params(sfx_id);
+        let (slot, evicted) = pool.allocate(sfx_id, &params)?;
+        pool.assign(slot, sfx_id, params.priority, handle);
+        Some(evicted)
+    }
+
+    #[test]
+    fn centered_sounds_play_at_full_volume_on_both_sides() {
+        let [left, right] = pan_gains(0.0);
+        assert!((left - 1.0).abs() < 1e-6 && (right - 1.0).abs() < 1e-6, "{} {}", left, right);
+    }
+
+    #[test]
+    fn panning_keeps_the_power_constant_across_the_stereo_field() {
+        let mut previous_left = f32::MAX;
+        for step in -10..=10 {
+            let [left, right] = pan_gains(step as f32 / 10.0);
+            assert!((left * left + right * right - 2.0).abs() < 1e-5, "pan {}: {} {}", step, left, right);
+            assert!(left <= previous_left, "pan {}: left gain rose", step);
+            previous_left = left;
+        }
+
+        let [left, right] = pan_gains(-1.0);
+        assert!((left - std::f32::consts::SQRT_2).abs() < 1e-6 && right.abs() < 1e-6);
+        assert_eq!(pan_gains(5.0), pan_gains(1.0));
+    }
+
+    #[test]
+    fn free_voices_are_allocated_before_anything_is_evicted() {
+        let mut pool = VoicePool::new(3);
+        assert_eq!(play(&mut pool, SfxId::Eat, "eat"), Some(None));
+        assert_eq!(play(&mut pool, SfxId::NewPerk, "new perk"), Some(None));
+        assert_eq!(play(&mut pool, SfxId::GameOver, "game over"), Some(None));
+
+        assert_eq!(pool.active_count(), 3);
+        assert!(pool.is_playing(SfxId::NewPerk));
+    }
+
+    #[test]
+    fn finished_voices_are_reaped() {
+        let mut pool = VoicePool::new(2);
+        play(&mut pool, SfxId::Eat, "finished");
+        play(&mut pool, SfxId::NewPerk, "playing");
+
+        pool.reap(|handle| *handle == "finished");
+
+        assert_eq!(pool.active_count(), 1);
+        assert!(!pool.is_playing(SfxId::Eat));
+        assert_eq!(play(&mut pool, SfxId::GameOver, "game over"), Some(None));
+        assert_eq!(pool.drain().len(), 2);
+        assert_eq!(pool.active_count(), 0);
+    }
+
+    #[test]
+    fn a_full_pool_steals_the_oldest_lowest_priority_voice() {
+        let mut pool = VoicePool::new(3);
+        play(&mut pool, SfxId::PerkLucky, "perk");
+        play(&mut pool, SfxId::Eat, "first eat");
+        play(&mut pool, SfxId::Eat, "second eat");
+
+        assert_eq!(play(&mut pool, SfxId::GameOver, "game over"), Some(Some("first eat")));
+        assert_eq!(play(&mut pool, SfxId::NewPerk, "new perk"), Some(Some("second eat")));
+        assert_eq!(play(&mut pool, SfxId::PerkCursed, "cursed"), Some(Some("perk")));
+    }
+
+    #[test]
+    fn a_full_pool_of_more_important_sounds_drops_the_new_one() {
+        let mut pool = VoicePool::new(2);
+        play(&mut pool, SfxId::GameOver, "game over");
+        play(&mut pool, SfxId::NewPerk, "new perk");
+
+        assert_eq!(play(&mut pool, SfxId::Eat, "eat"), None);
+        assert!(!pool.is_playing(SfxId::Eat));
+    }
+
+    #[test]
+    fn a_sound_over_its_instance_limit_cuts_off_its_own_oldest_instance() {
+        let mut pool = VoicePool::new(8);
+        for handle in ["eat 1", "eat 2", "eat 3", "eat 4"] {
+            assert_eq!(play(&mut pool, SfxId::Eat, handle), Some(None));
+        }
+        play(&mut pool, SfxId::NewPerk, "new perk");
+
+        assert_eq!(play(&mut pool, SfxId::Eat, "eat 5"), Some(Some("eat 1")));
+        assert_eq!(play(&mut pool, SfxId::NewPerk, "new perk again"), Some(Some("new perk")));
+        assert_eq!(pool.active_count(), 5);
+    }
+}
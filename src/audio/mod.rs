@@ -1 +1,6 @@
-pub mod manager;
\ No newline at end of file
+pub mod manager;
+pub mod backend;
+pub mod director;
+pub mod mixer;
+pub mod synth;
+pub mod preview;
\ No newline at end of file
@@ -0,0 +1,101 @@
+use crate::audio::synth::{parse_synth_definitions, SynthParams, SynthSource};
+use crate::state::constants::audio::SFX_DEFINITIONS_FILE;
+use rodio::{OutputStream, Sink};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Interactive tool for tweaking synthesized sound effects.
+///
+/// Plays the effect named `selected` (or every effect in turn) from the definitions file,
+/// and plays it again whenever the file is saved. Commands are read from stdin:
+/// an empty line replays, a section name switches effect, `all` plays everything and `q` quits.
+pub fn run_sfx_preview(selected: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let (_stream, stream_handle) = OutputStream::try_default()?;
+    let sink = Sink::try_new(&stream_handle)?;
+
+    // Read commands on a separate thread so file changes can be picked up while waiting
+    let (command_tx, command_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+            if command_tx.send(line.trim().to_string()).is_err() {
+                break;
+            }
+        }
+    });
+
+    println!("Previewing {} - edit and save it to hear the changes", SFX_DEFINITIONS_FILE);
+    println!("Commands: <enter> replay, <name> switch effect, all, q");
+
+    let mut selected = selected;
+    let mut last_modified = None;
+    let mut definitions = HashMap::new();
+
+    loop {
+        let modified = std::fs::metadata(SFX_DEFINITIONS_FILE).and_then(|metadata| metadata.modified()).ok();
+        let mut replay = modified != last_modified;
+
+        if replay {
+            last_modified = modified;
+            match load_definitions() {
+                Ok(loaded) => definitions = loaded,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    replay = false;
+                }
+            }
+        }
+
+        match command_rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(command) => match command.as_str() {
+                "q" | "quit" => return Ok(()),
+                "" => replay = true,
+                "all" => {
+                    selected = None;
+                    replay = true;
+                }
+                name => {
+                    selected = Some(name.to_string());
+                    replay = true;
+                }
+            },
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        if replay {
+            sink.stop();
+            play_definitions(&sink, &definitions, selected.as_deref());
+        }
+    }
+}
+
+fn load_definitions() -> Result<HashMap<String, SynthParams>, String> {
+    let text = std::fs::read_to_string(SFX_DEFINITIONS_FILE)
+        .map_err(|e| format!("Failed to read {}: {}", SFX_DEFINITIONS_FILE, e))?;
+
+    parse_synth_definitions(&text).map_err(|e| format!("{}: {}", SFX_DEFINITIONS_FILE, e))
+}
+
+fn play_definitions(sink: &Sink, definitions: &HashMap<String, SynthParams>, selected: Option<&str>) {
+    let mut names: Vec<&String> = definitions.keys().collect();
+    names.sort();
+
+    for name in names {
+        if selected.is_some_and(|selected| selected != name) {
+            continue;
+        }
+
+        let params = definitions[name];
+        println!("[{}] {:?}, {} Hz, {:.2} s", name, params.waveform, params.frequency, params.duration().as_secs_f32());
+
+        // Leave a short gap between effects when playing all of them
+        sink.append(SynthSource::new(params));
+        sink.append(SynthSource::new(SynthParams { volume: 0.0, ..SynthParams::default() }));
+    }
+
+    if let Some(selected) = selected.filter(|selected| !definitions.contains_key(*selected)) {
+        eprintln!("No effect named [{}] in {}", selected, SFX_DEFINITIONS_FILE);
+    }
+}
@@ -0,0 +1,280 @@
+use rodio::Source;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+pub const SYNTH_SAMPLE_RATE: u32 = 44_100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Square,
+    Triangle,
+    Sawtooth,
+    Noise,
+}
+
+/// Parameters describing a chiptune style sound effect, in the spirit of sfxr.
+/// Times are in seconds and frequencies in Hz.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SynthParams {
+    pub waveform: Waveform,
+    pub frequency: f32,
+    /// Change in frequency per second, negative values sweep downwards
+    pub frequency_sweep: f32,
+    /// The sweep never goes below this frequency
+    pub min_frequency: f32,
+    /// Portion of each square wave period that is high, between 0.0 and 1.0
+    pub duty: f32,
+    pub vibrato_depth: f32,
+    pub vibrato_speed: f32,
+    pub attack: f32,
+    pub sustain: f32,
+    pub decay: f32,
+    pub volume: f32,
+}
+
+impl Default for SynthParams {
+    fn default() -> Self {
+        SynthParams {
+            waveform: Waveform::Square,
+            frequency: 440.0,
+            frequency_sweep: 0.0,
+            min_frequency: 20.0,
+            duty: 0.5,
+            vibrato_depth: 0.0,
+            vibrato_speed: 0.0,
+            attack: 0.0,
+            sustain: 0.1,
+            decay: 0.1,
+            volume: 0.5,
+        }
+    }
+}
+
+impl SynthParams {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f32(self.attack + self.sustain + self.decay)
+    }
+
+    /// Amplitude envelope at time `t`: linear attack, flat sustain, linear decay
+    fn envelope(&self, t: f32) -> f32 {
+        if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.sustain {
+            1.0
+        } else if self.decay > 0.0 {
+            (1.0 - (t - self.attack - self.sustain) / self.decay).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Renders `SynthParams` sample by sample as a mono rodio `Source`
+pub struct SynthSource {
+    params: SynthParams,
+    sample_index: u32,
+    total_samples: u32,
+    phase: f32,
+    noise_state: u32,
+    noise_value: f32,
+}
+
+impl SynthSource {
+    pub fn new(params: SynthParams) -> Self {
+        SynthSource {
+            params,
+            sample_index: 0,
+            total_samples: (params.duration().as_secs_f32() * SYNTH_SAMPLE_RATE as f32) as u32,
+            phase: 0.0,
+            noise_state: 0xACE1,
+            noise_value: 0.0,
+        }
+    }
+
+    /// Next value of a 16-bit Galois LFSR, which keeps the noise identical on every play
+    fn next_noise(&mut self) -> f32 {
+        let bit = self.noise_state & 1;
+        self.noise_state >>= 1;
+        if bit == 1 {
+            self.noise_state ^= 0xB400;
+        }
+        (self.noise_state as f32 / 0x7FFF as f32) - 1.0
+    }
+}
+
+impl Iterator for SynthSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.sample_index >= self.total_samples {
+            return None;
+        }
+
+        let t = self.sample_index as f32 / SYNTH_SAMPLE_RATE as f32;
+        self.sample_index += 1;
+
+        let mut frequency = (self.params.frequency + self.params.frequency_sweep * t).max(self.params.min_frequency);
+        if self.params.vibrato_depth > 0.0 {
+            frequency *= 1.0 + self.params.vibrato_depth * (2.0 * PI * self.params.vibrato_speed * t).sin();
+        }
+
+        self.phase += frequency / SYNTH_SAMPLE_RATE as f32;
+        if self.phase >= 1.0 {
+            self.phase -= self.phase.floor();
+            if self.params.waveform == Waveform::Noise {
+                self.noise_value = self.next_noise();
+            }
+        }
+
+        let value = match self.params.waveform {
+            Waveform::Square => if self.phase < self.params.duty { 1.0 } else { -1.0 },
+            Waveform::Triangle => 4.0 * (self.phase - 0.5).abs() - 1.0,
+            Waveform::Sawtooth => 2.0 * self.phase - 1.0,
+            Waveform::Noise => self.noise_value,
+        };
+
+        Some(value * self.params.envelope(t) * self.params.volume)
+    }
+}
+
+impl Source for SynthSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some((self.total_samples - self.sample_index) as usize)
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SYNTH_SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(self.params.duration())
+    }
+}
+
+/// Parses sound effect definitions from an INI style text, one `[section]` per effect:
+///
+/// ```text
+/// [new_perk]
+/// waveform = square
+/// frequency = 440
+/// frequency_sweep = 900
+/// ```
+///
+/// Keys that are left out keep their `SynthParams::default()` value. `#` and `;` start comments.
+pub fn parse_synth_definitions(text: &str) -> Result<HashMap<String, SynthParams>, String> {
+    let mut definitions = HashMap::new();
+    let mut current: Option<(String, SynthParams)> = None;
+
+    for (line_index, raw_line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = raw_line.split(['#', ';']).next().unwrap_or("").trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            if let Some((name, params)) = current.take() {
+                definitions.insert(name, params);
+            }
+            current = Some((name.trim().to_string(), SynthParams::default()));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `key = value`, found `{}`", line_number, line));
+        };
+
+        let Some((_, params)) = current.as_mut() else {
+            return Err(format!("line {}: `{}` appears before any [section]", line_number, key.trim()));
+        };
+
+        let (key, value) = (key.trim(), value.trim());
+        let number = || value.parse::<f32>().map_err(|_| format!("line {}: `{}` is not a number", line_number, value));
+
+        match key {
+            "waveform" => {
+                params.waveform = match value {
+                    "square" => Waveform::Square,
+                    "triangle" => Waveform::Triangle,
+                    "sawtooth" => Waveform::Sawtooth,
+                    "noise" => Waveform::Noise,
+                    _ => return Err(format!("line {}: unknown waveform `{}`", line_number, value)),
+                }
+            }
+            "frequency" => params.frequency = number()?,
+            "frequency_sweep" => params.frequency_sweep = number()?,
+            "min_frequency" => params.min_frequency = number()?,
+            "duty" => params.duty = number()?.clamp(0.0, 1.0),
+            "vibrato_depth" => params.vibrato_depth = number()?,
+            "vibrato_speed" => params.vibrato_speed = number()?,
+            "attack" => params.attack = number()?.max(0.0),
+            "sustain" => params.sustain = number()?.max(0.0),
+            "decay" => params.decay = number()?.max(0.0),
+            "volume" => params.volume = number()?.clamp(0.0, 1.0),
+            _ => return Err(format!("line {}: unknown key `{}`", line_number, key)),
+        }
+    }
+
+    if let Some((name, params)) = current {
+        definitions.insert(name, params);
+    }
+
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn left_out_keys_keep_their_defaults() {
+        let definitions = parse_synth_definitions("[blip]\nwaveform = noise\nfrequency = 880\n\n[empty]\n").unwrap();
+
+        let blip = SynthParams { waveform: Waveform::Noise, frequency: 880.0, ..SynthParams::default() };
+        assert_eq!(definitions["blip"], blip);
+        assert_eq!(definitions["empty"], SynthParams::default());
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_ignored() {
+        let text = "# Sound effects\n\n[ blip ] ; padded name\nvolume = 0.25 # quieter\n; frequency = 100\n";
+        let definitions = parse_synth_definitions(text).unwrap();
+
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions["blip"], SynthParams { volume: 0.25, ..SynthParams::default() });
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let definitions = parse_synth_definitions("[blip]\nduty = 2\nvolume = -1\ndecay = -0.5\n").unwrap();
+
+        assert_eq!(definitions["blip"].duty, 1.0);
+        assert_eq!(definitions["blip"].volume, 0.0);
+        assert_eq!(definitions["blip"].decay, 0.0);
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_their_line() {
+        let error = parse_synth_definitions("[blip]\nfrequency = 440\nloudness = 1\n").unwrap_err();
+        assert_eq!(error, "line 3: unknown key `loudness`");
+    }
+
+    #[test]
+    fn unknown_waveforms_are_reported_with_their_line() {
+        let error = parse_synth_definitions("[blip]\nwaveform = sine\n").unwrap_err();
+        assert_eq!(error, "line 2: unknown waveform `sine`");
+    }
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        assert_eq!(parse_synth_definitions("frequency = 440\n").unwrap_err(), "line 1: `frequency` appears before any [section]");
+        assert_eq!(parse_synth_definitions("[blip]\nfrequency\n").unwrap_err(), "line 2: expected `key = value`, found `frequency`");
+        assert_eq!(parse_synth_definitions("[blip]\nfrequency = high\n").unwrap_err(), "line 2: `high` is not a number");
+    }
+}
@@ -0,0 +1,116 @@
+use space_worm::ai::{ControllerKind, Difficulty};
+use space_worm::gym::observation::ObservationEncoding;
+use space_worm::gym::tournament::{play_game, summarize, Contestant, Summary};
+use space_worm::gym::Environment;
+use space_worm::state::constants::gym::{TOURNAMENT_GAMES, TOURNAMENT_MAX_STEPS, TOURNAMENT_SEEDS};
+
+/// Pits controllers and external bots against the same seeds and reports how each did.
+///
+/// ```text
+/// tournament [--controller greedy|astar|floodfill]... [--bot "command"]... [--games N] [--seeds 1,2,3]
+///            [--difficulty easy|normal|hard] [--max-steps N] [--csv file] [--json file]
+/// ```
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let value_of = |flag: &str| args.iter().position(|arg| arg == flag).and_then(|index| args.get(index + 1));
+    let values_of = |flag: &str| args.windows(2).filter(|pair| pair[0] == flag).map(|pair| pair[1].clone()).collect::<Vec<_>>();
+
+    let mut contestants: Vec<Contestant> = values_of("--controller")
+        .iter()
+        .filter_map(|name| {
+            let kind = ControllerKind::from_name(name);
+            if kind.is_none() {
+                eprintln!("Unknown controller: {}", name);
+            }
+            kind.map(Contestant::Controller)
+        })
+        .chain(values_of("--bot").into_iter().map(Contestant::External))
+        .collect();
+    if contestants.is_empty() {
+        contestants = [ControllerKind::Greedy, ControllerKind::AStar, ControllerKind::FloodFill].map(Contestant::Controller).to_vec();
+    }
+
+    let games = value_of("--games").and_then(|games| games.parse().ok()).unwrap_or(TOURNAMENT_GAMES);
+    let seeds: Vec<u64> = value_of("--seeds")
+        .map(|seeds| seeds.split(',').filter_map(|seed| seed.trim().parse().ok()).collect())
+        .filter(|seeds: &Vec<u64>| !seeds.is_empty())
+        .unwrap_or_else(|| TOURNAMENT_SEEDS.to_vec());
+    let difficulty = value_of("--difficulty").and_then(|name| Difficulty::from_name(name)).unwrap_or(Difficulty::HARD);
+    let max_steps = value_of("--max-steps").and_then(|steps| steps.parse().ok()).unwrap_or(TOURNAMENT_MAX_STEPS);
+
+    let mut environment = Environment::new(ObservationEncoding::Features);
+    let mut summaries = Vec::new();
+
+    for contestant in contestants.iter() {
+        let mut results = Vec::with_capacity(games);
+        for game in 0..games {
+            let seed = seeds[game % seeds.len()];
+            match play_game(&mut environment, contestant, difficulty, seed, max_steps) {
+                Ok(result) => results.push(result),
+                Err(e) => eprintln!("{} failed on seed {}: {}", contestant.name(), seed, e),
+            }
+        }
+        summaries.push(summarize(contestant, results));
+    }
+
+    print_table(&summaries);
+
+    if let Some(path) = value_of("--csv") {
+        if let Err(e) = std::fs::write(path, format_csv(&summaries)) {
+            eprintln!("Failed to write CSV report to {}: {}", path, e);
+        }
+    }
+    if let Some(path) = value_of("--json") {
+        let json = serde_json::to_string_pretty(&summaries).expect("Summaries serialize to JSON");
+        if let Err(e) = std::fs::write(path, json) {
+            eprintln!("Failed to write JSON report to {}: {}", path, e);
+        }
+    }
+}
+
+fn print_table(summaries: &[Summary]) {
+    let name_width = summaries.iter().map(|summary| summary.contestant.len()).max().unwrap_or(0).max("Contestant".len());
+
+    println!(
+        "{:<name_width$}  {:>5}  {:>10}  {:>10}  {:>9}  {:>9}  {:>6}  {:>6}  {:>6}  {:>8}",
+        "Contestant", "Games", "Mean score", "Median", "Mean time", "Median", "Self", "Worm", "Hazard", "Survived"
+    );
+    for summary in summaries {
+        println!(
+            "{:<name_width$}  {:>5}  {:>10.0}  {:>10.0}  {:>8.1}s  {:>8.1}s  {:>6}  {:>6}  {:>6}  {:>8}",
+            summary.contestant,
+            summary.games,
+            summary.mean_score,
+            summary.median_score,
+            summary.mean_survival_time,
+            summary.median_survival_time,
+            summary.self_collisions,
+            summary.worm_collisions,
+            summary.hazard_deaths,
+            summary.survived,
+        );
+    }
+}
+
+/// One row per contestant, game by game results are only in the JSON report
+fn format_csv(summaries: &[Summary]) -> String {
+    let mut csv = String::from(
+        "contestant,games,mean_score,median_score,mean_survival_time,median_survival_time,self_collisions,worm_collisions,hazard_deaths,survived\n",
+    );
+    for summary in summaries {
+        csv.push_str(&format!(
+            "\"{}\",{},{:.1},{:.1},{:.2},{:.2},{},{},{},{}\n",
+            summary.contestant.replace('"', "\"\""),
+            summary.games,
+            summary.mean_score,
+            summary.median_score,
+            summary.mean_survival_time,
+            summary.median_survival_time,
+            summary.self_collisions,
+            summary.worm_collisions,
+            summary.hazard_deaths,
+            summary.survived,
+        ));
+    }
+    csv
+}
@@ -3,10 +3,15 @@ use crate::state::structs::GameState;
 
 pub fn render_pixel_buffer(game_state: &mut GameState) {
     // Scale the buffer to the screen resolution
+    // A headless game has nowhere to show it
+    let Some(window) = game_state.window.as_mut() else {
+        return;
+    };
+
     scale_buffer(&game_state.window_buffer, &mut game_state.scaled_buffer, ART_WIDTH, ART_HEIGHT, game_state.window_width, game_state.window_height);
 
     // Draw the scaled buffer onto the window
-    game_state.window.update_with_buffer(&game_state.scaled_buffer, game_state.window_width, game_state.window_height).unwrap();
+    window.update_with_buffer(&game_state.scaled_buffer, game_state.window_width, game_state.window_height).unwrap();
 }
 
 // Function to scale a buffer to a different resolution
@@ -1,4 +1,8 @@
+use crate::state::constants::graphics::WORM_SKIN_TINTS;
+use crate::state::food::FOOD_CATALOGUE;
+use crate::state::structs::FoodKind;
 use image::GenericImageView;
+use std::collections::HashMap;
 
 pub struct SpriteFrame {
     pub width: u32,  // Width of the sprite in pixels
@@ -10,13 +14,30 @@ impl SpriteFrame {
     fn new(width: u32, height: u32, data: Vec<u32>) -> Self {
         Self { width, height, data }
     }
+
+    /// Copy of the sprite with every pixel's color multiplied by `tint`, keeping its alpha
+    pub fn tinted(&self, tint: u32) -> Self {
+        let data = self.data.iter().map(|pixel| {
+            let channel = |shift: u32| ((pixel >> shift) & 0xFF) * ((tint >> shift) & 0xFF) / 255;
+            (pixel & 0xFF000000) | (channel(16) << 16) | (channel(8) << 8) | channel(0)
+        }).collect();
+
+        Self::new(self.width, self.height, data)
+    }
 }
 
-pub struct SpriteMaps {
+/// Head, body and tail sprites of a worm in one color scheme
+pub struct WormSkin {
     pub body: Vec<SpriteFrame>,
-    pub food: Vec<SpriteFrame>,
     pub head: Vec<SpriteFrame>,
     pub tail: Vec<SpriteFrame>,
+}
+
+pub struct SpriteMaps {
+    /// The worm sprites tinted with each of `WORM_SKIN_TINTS`
+    pub worm_skins: Vec<WormSkin>,
+    /// Food sprites tinted for each kind of food in the catalogue
+    pub food: HashMap<FoodKind, Vec<SpriteFrame>>,
     pub game_over_screen: Vec<SpriteFrame>,
     pub stars: Vec<SpriteFrame>,
     pub planet: Vec<SpriteFrame>,
@@ -25,13 +46,32 @@ pub struct SpriteMaps {
     pub choose_perk: Vec<SpriteFrame>
 }
 
+impl Default for SpriteMaps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SpriteMaps {
     pub fn new() -> Self {
+        let food_frames = load_sprites_from_map("assets/sprites/food.png", 16, 16);
+        let food = FOOD_CATALOGUE
+            .iter()
+            .map(|definition| (definition.kind, food_frames.iter().map(|frame| frame.tinted(definition.tint)).collect()))
+            .collect();
+
+        let body = load_sprites_from_map("assets/sprites/body.png", 6, 6);
+        let head = load_sprites_from_map("assets/sprites/head.png", 16, 16);
+        let tail = load_sprites_from_map("assets/sprites/tail.png", 6, 6);
+        let tint_all = |frames: &[SpriteFrame], tint: u32| frames.iter().map(|frame| frame.tinted(tint)).collect();
+        let worm_skins = WORM_SKIN_TINTS
+            .iter()
+            .map(|&tint| WormSkin { body: tint_all(&body, tint), head: tint_all(&head, tint), tail: tint_all(&tail, tint) })
+            .collect();
+
         Self {
-            body: load_sprites_from_map("assets/sprites/body.png", 6, 6),
-            food: load_sprites_from_map("assets/sprites/food.png", 16, 16),
-            head: load_sprites_from_map("assets/sprites/head.png", 16, 16),
-            tail: load_sprites_from_map("assets/sprites/tail.png", 6, 6),
+            worm_skins,
+            food,
             game_over_screen: load_sprites_from_map("assets/sprites/game_over.png", 256, 224),
             stars: load_sprites_from_map("assets/sprites/layer_0.png", 256, 224),
             planet: load_sprites_from_map("assets/sprites/layer_1.png", 256, 224),
@@ -40,6 +80,21 @@ impl SpriteMaps {
             choose_perk: load_sprites_from_map("assets/sprites/choose_perk.png", 256, 112)
         }
     }
+
+    /// No sprites at all, for games that are never drawn: nothing is read from disk,
+    /// so they run from any working directory
+    pub fn empty() -> Self {
+        Self {
+            worm_skins: Vec::new(),
+            food: HashMap::new(),
+            game_over_screen: Vec::new(),
+            stars: Vec::new(),
+            planet: Vec::new(),
+            blue_strip: Vec::new(),
+            perks: Vec::new(),
+            choose_perk: Vec::new()
+        }
+    }
 }
 
 /// Loads sprites from a sprite map image file into memory.
@@ -56,7 +111,7 @@ impl SpriteMaps {
 /// A vector containing tuples of sprite dimensions and pixel data.
 pub fn load_sprites_from_map(sprite_map_path: &str, sprite_width: u32, sprite_height: u32) -> Vec<SpriteFrame> {
     // Load the sprite map image
-    let sprite_map = image::open(sprite_map_path).expect(&format!("Failed to open sprite map at {}", sprite_map_path));
+    let sprite_map = image::open(sprite_map_path).unwrap_or_else(|_| panic!("Failed to open sprite map at {}", sprite_map_path));
     let (map_width, map_height) = sprite_map.dimensions();
 
     // Calculate the number of sprites in each dimension
@@ -116,7 +171,7 @@ pub fn img_to_buffer(img: &image::DynamicImage) -> Vec<u32> {
 /// - An alpha value of 255 (0xFF) means the pixel is completely opaque.
 ///
 /// The formula for alpha blending is:
-/// ```
+/// ```text
 /// blended_color = (foreground_color * alpha + background_color * (255 - alpha)) / 255
 /// ```
 ///
@@ -170,6 +225,52 @@ pub fn draw_sprite(
     }
 }
 
+/// Draws a sprite onto the window buffer resized to `target_width` x `target_height`, with alpha blending.
+///
+/// Uses nearest neighbour sampling so pixel art stays crisp. Otherwise behaves like `draw_sprite`.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_sprite_scaled(
+    x: usize,
+    y: usize,
+    sprite: &SpriteFrame,
+    target_width: usize,
+    target_height: usize,
+    window_buffer: &mut [u32],
+    window_width: usize,
+    darkness_factor: Option<f32>
+) {
+    for row in 0..target_height {
+        for col in 0..target_width {
+            let sprite_row = row * sprite.height as usize / target_height;
+            let sprite_col = col * sprite.width as usize / target_width;
+            let sprite_pixel_index = sprite_row * (sprite.width as usize) + sprite_col;
+            let window_pixel_index = (y + row) * window_width + (x + col);
+
+            if window_pixel_index < window_buffer.len() {
+                let mut sprite_pixel = sprite.data[sprite_pixel_index];
+
+                // Apply darkening if specified
+                maybe_darken(&mut sprite_pixel, darkness_factor);
+
+                let sprite_alpha = (sprite_pixel >> 24) & 0xFF;
+                let sprite_rgb = sprite_pixel & 0x00FFFFFF;
+
+                if sprite_alpha > 0 {
+                    let window_pixel = window_buffer[window_pixel_index];
+                    let window_rgb = window_pixel & 0x00FFFFFF;
+
+                    let blended_r = ((sprite_rgb >> 16) & 0xFF) * sprite_alpha / 255 + ((window_rgb >> 16) & 0xFF) * (255 - sprite_alpha) / 255;
+                    let blended_g = ((sprite_rgb >> 8) & 0xFF) * sprite_alpha / 255 + ((window_rgb >> 8) & 0xFF) * (255 - sprite_alpha) / 255;
+                    let blended_b = (sprite_rgb & 0xFF) * sprite_alpha / 255 + (window_rgb & 0xFF) * (255 - sprite_alpha) / 255;
+
+                    let blended_pixel = 0xFF000000 | (blended_r & 0xFF) << 16 | (blended_g & 0xFF) << 8 | (blended_b & 0xFF);
+                    window_buffer[window_pixel_index] = blended_pixel;
+                }
+            }
+        }
+    }
+}
+
 /// Draws a sprite onto the window buffer with gradient shading applied to each pixel.
 ///
 /// # Parameters
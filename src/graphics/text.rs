@@ -20,12 +20,35 @@ impl BitFont {
     ///
     /// Characters are spaced horizontally based on the scaled width of each character.
     /// Non-printable characters are skipped.
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_text_smooth_scaled(&self, framebuffer: &mut [u32], fb_width: usize, text: &str, x: i32, y: i32, color: u32, scale: f32) {
+        self.for_each_text_pixel(framebuffer, fb_width, text, x, y, scale, |pixel| *pixel = color);
+    }
+
+    /// Like `draw_text_smooth_scaled`, but blends the text over what is already drawn.
+    /// `opacity` goes from 0.0 (invisible) to 1.0 (same as `draw_text_smooth_scaled`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text_faded(&self, framebuffer: &mut [u32], fb_width: usize, text: &str, x: i32, y: i32, color: u32, scale: f32, opacity: f32) {
+        let opacity = opacity.clamp(0.0, 1.0);
+
+        self.for_each_text_pixel(framebuffer, fb_width, text, x, y, scale, |pixel| {
+            let blend = |shift: u32| {
+                let text_channel = ((color >> shift) & 0xFF) as f32;
+                let background_channel = ((*pixel >> shift) & 0xFF) as f32;
+                ((text_channel * opacity + background_channel * (1.0 - opacity)) as u32).min(255)
+            };
+            *pixel = 0xFF000000 | (blend(16) << 16) | (blend(8) << 8) | blend(0);
+        });
+    }
+
+    /// Calls `plot` for every framebuffer pixel covered by `text`, which is laid out as described on `draw_text_smooth_scaled`
+    #[allow(clippy::too_many_arguments)]
+    fn for_each_text_pixel(&self, framebuffer: &mut [u32], fb_width: usize, text: &str, x: i32, y: i32, scale: f32, mut plot: impl FnMut(&mut u32)) {
         let char_width = (8.0 * scale) as i32;
         let mut current_x = x;
 
         // Iterate over each character in the text
-        for (_char_idx, ch) in text.chars().enumerate() {
+        for ch in text.chars() {
             let char_index = (ch as u8).saturating_sub(32) as usize;
             if char_index >= 95 { // Check if character is printable
                 continue;
@@ -60,7 +83,7 @@ impl BitFont {
                                 (screen_y as usize * fb_width + screen_x as usize) < framebuffer.len() {
 
                                 // Set the pixel color in the framebuffer
-                                framebuffer[screen_y as usize * fb_width + screen_x as usize] = color;
+                                plot(&mut framebuffer[screen_y as usize * fb_width + screen_x as usize]);
                             }
                         }
                     }
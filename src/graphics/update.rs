@@ -1,14 +1,101 @@
-use crate::graphics::sprites::{draw_sprite, draw_sprite_with_gradient_shading};
+use crate::graphics::sprites::{draw_sprite, draw_sprite_scaled, draw_sprite_with_gradient_shading, SpriteMaps};
 use crate::graphics::text::{get_font_data, BitFont};
-use crate::state::constants::graphics::{ART_HEIGHT, ART_WIDTH};
-use crate::state::constants::text::{PERK_CURSE_OF_GLOSSY, PERK_HUNGRY_WORM, PERK_NEED_4_SPEED, SCORE, SELECT_PERK};
-use crate::state::structs::{Direction, GameState};
+use crate::state::constants::graphics::{ART_HEIGHT, ART_WIDTH, WORM_SKIN_TINTS};
+use crate::state::constants::state::{
+    COMBO_WINDOW_SECONDS, FOOD_BLINK_SECONDS, GLOSSY_HAZARD_LIFETIME, PERK_REROLL_COST, SCORE_POPUP_RISE, SCORE_POPUP_SECONDS,
+};
+use crate::state::food::food_definition;
+use crate::state::constants::text::{
+    BEST, BUILD, DEMO, DEMO_HINT, GAME_OVER_HINT, MATCH_WINNER, NO_PERKS_TAKEN, PAUSED, PAUSE_HINT, REROLL_PERKS, ROUND_DRAW,
+    ROUND_WINNER, SCORE, SELECT_PERK, TITLE, TITLE_HINT,
+};
+use crate::state::perks::{perk_definition, timed_effect_perk};
+use crate::state::structs::{Direction, FoodKind, GameState, Perk, Snake, TimedEffect};
 
 pub fn update_pixel_buffer(game_state: &mut GameState) {
     draw_background(game_state);
     draw_food(game_state);
-    draw_player(game_state);
-    draw_score(game_state);
+    draw_hazards(game_state);
+    draw_worms(game_state);
+    draw_score_popups(game_state);
+    if game_state.is_versus() {
+        draw_versus_scores(game_state);
+    } else {
+        draw_score(game_state);
+        draw_combo(game_state);
+        draw_rival_scores(game_state);
+    }
+    draw_timed_effects(game_state);
+}
+
+/// Score popups float up from where the food was eaten, fading out as they go
+fn draw_score_popups(game_state: &mut GameState) {
+    let bit_font = BitFont { chars: get_font_data() };
+
+    for popup in game_state.score_popups.iter() {
+        let progress = popup.age / SCORE_POPUP_SECONDS;
+        let x = popup.position.x as i32 + 8 - (popup.text.len() as i32 * 4); // Centered over the food sprite
+        let y = (popup.position.y - progress * SCORE_POPUP_RISE) as i32;
+
+        bit_font.draw_text_faded(&mut game_state.window_buffer, ART_WIDTH, &popup.text, x, y, 0xFFD700, 1.0, 1.0 - progress);
+    }
+}
+
+/// Current combo multiplier in the top right corner, dimming as the combo is about to drop
+fn draw_combo(game_state: &mut GameState) {
+    if game_state.combo <= 1 {
+        return;
+    }
+
+    let combo_text = format!("x{}", game_state.combo);
+    let opacity = 0.4 + 0.6 * (game_state.combo_timer / COMBO_WINDOW_SECONDS);
+    let x = ART_WIDTH as i32 - 4 - (combo_text.len() as i32 * 12);
+
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_faded(&mut game_state.window_buffer, ART_WIDTH, &combo_text, x, 6, 0xFFD700, 1.5, opacity);
+}
+
+/// Lists the running timed effects in the top left corner, each with its perk's icon and the seconds left
+fn draw_timed_effects(game_state: &mut GameState) {
+    let bit_font = BitFont { chars: get_font_data() };
+    let icon_width = 16;
+    let icon_height = 14;
+
+    for (i, active) in game_state.player.active_effects.iter().enumerate() {
+        let y = 4 + i * (icon_height + 2);
+
+        // Effects from perks show the perk's card, the speed rush shows the food that gave it
+        let icon = match timed_effect_perk(active.effect) {
+            Some(definition) => game_state.sprites.perks.get(definition.icon_frame),
+            None => game_state.sprites.food[&FoodKind::Speed].first(),
+        };
+
+        if let Some(icon) = icon {
+            draw_sprite_scaled(
+                4,
+                y,
+                icon,
+                icon_width,
+                icon_height,
+                &mut game_state.window_buffer,
+                ART_WIDTH,
+                None
+            );
+        }
+
+        // Turn the countdown red when the effect is about to wear off
+        let seconds_left = active.remaining.ceil() as u32;
+        let color = if active.remaining < 3.0 { 0xFF5555 } else { 0xFFFFFF };
+        bit_font.draw_text_smooth_scaled(
+            &mut game_state.window_buffer,
+            ART_WIDTH,
+            &format!("{}s", seconds_left),
+            6 + icon_width as i32,
+            y as i32 + 3,
+            color,
+            1.0
+        );
+    }
 }
 
 fn draw_score(game_state: &mut GameState) {
@@ -28,28 +115,117 @@ fn draw_score(game_state: &mut GameState) {
     }
 }
 
+/// Each player's score in their worm's color, with the rounds won so far in between
+fn draw_versus_scores(game_state: &mut GameState) {
+    let bit_font = BitFont { chars: get_font_data() };
+    let player_one_color = WORM_SKIN_TINTS[game_state.player.skin];
+    let player_one_text = format!("P1 {}", game_state.score);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, &player_one_text, 6, 10, player_one_color, 1.0);
+
+    if let Some(rival) = game_state.rivals.first() {
+        let player_two_color = WORM_SKIN_TINTS[rival.snake.skin];
+        let player_two_text = format!("P2 {}", rival.score);
+        let x = ART_WIDTH as i32 - 6 - player_two_text.len() as i32 * 8;
+        bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, &player_two_text, x, 10, player_two_color, 1.0);
+    }
+
+    let wins_text = format!("{}-{}", game_state.round_wins[0], game_state.round_wins[1]);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, &wins_text, centered_text_x(&wins_text, 1.0), 10, 0xFFFFFF, 1.0);
+}
+
+/// Computer-controlled rivals' scores down the right edge, each in its worm's color
+fn draw_rival_scores(game_state: &mut GameState) {
+    let bit_font = BitFont { chars: get_font_data() };
+
+    for (index, rival) in game_state.rivals.iter().enumerate() {
+        let text = rival.score.to_string();
+        let x = ART_WIDTH as i32 - 6 - text.len() as i32 * 8;
+        let y = 10 + index as i32 * 10;
+        let opacity = if rival.alive { 1.0 } else { 0.4 };
+        bit_font.draw_text_faded(&mut game_state.window_buffer, ART_WIDTH, &text, x, y, WORM_SKIN_TINTS[rival.snake.skin], 1.0, opacity);
+    }
+}
+
 fn draw_food(game_state: &mut GameState) {
-    let food_x = game_state.food.position.x;
-    let darkness = calculate_darkness(food_x);
+    for food in game_state.foods.iter() {
+        let definition = food_definition(food.kind);
 
-    // Draw the food sprite at the food's position
-    draw_sprite(
-        food_x as usize,
-        game_state.food.position.y as usize,
-        &game_state.sprites.food[*&game_state.food.food_sprite_frame_index],
-        game_state.window_buffer,
-        ART_WIDTH,
-        darkness
-    );
+        // Food about to expire blinks
+        let expiring = definition.lifetime.is_some_and(|lifetime| food.age > lifetime - FOOD_BLINK_SECONDS);
+        if expiring && ((food.age * 8.0) as u32).is_multiple_of(2) {
+            continue;
+        }
+
+        let food_x = food.position.x;
+        let darkness = calculate_darkness(food_x);
+
+        // Draw the food sprite at the food's position
+        draw_sprite(
+            food_x as usize,
+            food.position.y as usize,
+            &game_state.sprites.food[&food.kind][food.food_sprite_frame_index],
+            &mut game_state.window_buffer,
+            ART_WIDTH,
+            darkness
+        );
+    }
 }
 
-fn draw_player(game_state: &mut GameState) {
+/// Draws the Curse of Glossy hazards as twinkling four pointed sparkles, blinking before they vanish
+fn draw_hazards(game_state: &mut GameState) {
+    // Sparkle shape centered on the hazard position: 0 = empty, 1 = rim, 2 = core
+    const SPARKLE: [[u8; 7]; 7] = [
+        [0, 0, 0, 1, 0, 0, 0],
+        [0, 0, 0, 1, 0, 0, 0],
+        [0, 0, 1, 2, 1, 0, 0],
+        [1, 1, 2, 2, 2, 1, 1],
+        [0, 0, 1, 2, 1, 0, 0],
+        [0, 0, 0, 1, 0, 0, 0],
+        [0, 0, 0, 1, 0, 0, 0],
+    ];
+    const RIM_COLORS: [u32; 3] = [0xFFD700, 0x7FFFD4, 0xFF77FF];
+
+    for hazard in game_state.hazards.iter() {
+        if hazard.age > GLOSSY_HAZARD_LIFETIME - 2.0 && ((hazard.age * 8.0) as u32).is_multiple_of(2) {
+            continue;
+        }
+
+        let rim_color = RIM_COLORS[(hazard.age * 6.0) as usize % RIM_COLORS.len()];
 
-    let head_position = &game_state.player.body[0];
+        for (row, pixels) in SPARKLE.iter().enumerate() {
+            for (col, pixel) in pixels.iter().enumerate() {
+                let color = match pixel {
+                    1 => rim_color,
+                    2 => 0xFFFFFF,
+                    _ => continue,
+                };
+
+                let x = hazard.position.x as i32 + col as i32 - 3;
+                let y = hazard.position.y as i32 + row as i32 - 3;
+                if x >= 0 && (x as usize) < ART_WIDTH && y >= 0 && (y as usize) < ART_HEIGHT {
+                    game_state.window_buffer[y as usize * ART_WIDTH + x as usize] = 0xFF000000 | color;
+                }
+            }
+        }
+    }
+}
+
+fn draw_worms(game_state: &mut GameState) {
+    draw_snake(&game_state.player, &game_state.sprites, &mut game_state.window_buffer);
+
+    for rival in game_state.rivals.iter().filter(|rival| rival.alive) {
+        draw_snake(&rival.snake, &game_state.sprites, &mut game_state.window_buffer);
+    }
+}
+
+fn draw_snake(snake: &Snake, sprites: &SpriteMaps, window_buffer: &mut [u32]) {
+    let skin = &sprites.worm_skins[snake.skin];
+
+    let head_position = &snake.body[0];
     let darkness = calculate_darkness(head_position.x);
 
     // Magic number offset based on direction
-    let offset: f32 = match game_state.player.direction {
+    let offset: f32 = match snake.direction {
         Direction::Right => 0.0,
         Direction::Left => 10.0,
         Direction::Up => 7.0,
@@ -60,44 +236,51 @@ fn draw_player(game_state: &mut GameState) {
     draw_sprite(
         (head_position.x - offset) as usize,
         (head_position.y - offset) as usize,
-        &game_state.sprites.head[game_state.player.head_sprite_frame_index],
-        game_state.window_buffer,
+        &skin.head[snake.head_sprite_frame_index],
+        window_buffer,
         ART_WIDTH,
         darkness
     );
 
 
+    // A ghostly worm's body is drawn faded
+    let is_ghost = snake.has_timed_effect(TimedEffect::Ghost);
+
     // Draw the body segments from neck to buttocks
-    for i in 1..game_state.player.body.len() -1 {
-        let body_x = game_state.player.body[i].x;
-        let darkness = calculate_darkness(body_x);
+    for i in 1..snake.body.len() -1 {
+        let body_x = snake.body[i].x;
+        let darkness = if is_ghost {
+            Some(calculate_darkness(body_x).unwrap_or(1.0) * 0.5)
+        } else {
+            calculate_darkness(body_x)
+        };
 
         draw_sprite(
             body_x as usize,
-            game_state.player.body[i].y as usize,
-            &game_state.sprites.body[game_state.player.body_sprite_frame_index],
-            game_state.window_buffer,
+            snake.body[i].y as usize,
+            &skin.body[snake.body_sprite_frame_index],
+            window_buffer,
             ART_WIDTH,
             darkness
         );
     }
 
     // For right and up we draw the first tail sprite frame, left and down we draw the second tail sprite frame
-    let tail_sprite_index = if game_state.player.direction == Direction::Right || game_state.player.direction == Direction::Up {
+    let tail_sprite_index = if snake.direction == Direction::Right || snake.direction == Direction::Up {
         0
     } else {
         1
     };
 
-    let tail_index = game_state.player.body.len();
+    let tail_index = snake.body.len();
     if tail_index > 0 {
-        let tail_position = &game_state.player.body[tail_index - 1];
+        let tail_position = &snake.body[tail_index - 1];
         let darkness = calculate_darkness(tail_position.x);
         draw_sprite(
             tail_position.x as usize,
             tail_position.y as usize,
-            &game_state.sprites.tail[tail_sprite_index],
-            game_state.window_buffer,
+            &skin.tail[tail_sprite_index],
+            window_buffer,
             ART_WIDTH,
             darkness
         );
@@ -110,7 +293,7 @@ pub fn draw_game_over_screen(game_state: &mut GameState, index: usize, darkness_
         0,
         0,
         &game_state.sprites.game_over_screen[index],
-        game_state.window_buffer,
+        &mut game_state.window_buffer,
         ART_WIDTH,
         darkness_factor
     );
@@ -124,14 +307,155 @@ pub fn draw_game_over_screen(game_state: &mut GameState, index: usize, darkness_
     bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, &score_text, x_position, y_position as i32, 0xFFFFFF, 1.0);
 }
 
-pub fn draw_choose_perk_screen_with_highlight(game_state: &mut GameState, highlighted_perk: Option<usize>) {
+/// Score tab of the game over screen: the last game over frame with the score, the best score so far and controls
+pub fn draw_game_over_summary(game_state: &mut GameState) {
+    let last_frame = game_state.sprites.game_over_screen.len() - 1;
+    draw_game_over_screen(game_state, last_frame, Some(0.8));
+
+    let best_score = game_state.high_scores.first().map_or(game_state.score, |entry| entry.score.max(game_state.score));
+    let best_text = format!("{}{}", BEST, best_score);
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, &best_text, centered_text_x(&best_text, 1.0), ART_HEIGHT as i32 - 32, 0xFFD700, 1.0);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, GAME_OVER_HINT, centered_text_x(GAME_OVER_HINT, 1.0), 6, 0xCCCCCC, 1.0);
+}
+
+/// Build tab of the game over screen
+pub fn draw_game_over_build(game_state: &mut GameState) {
+    let last_frame = game_state.sprites.game_over_screen.len() - 1;
+    draw_game_over_screen(game_state, last_frame, Some(0.2));
+    darken_window_buffer(game_state, 0.3);
+    draw_build_log(game_state);
+
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, GAME_OVER_HINT, centered_text_x(GAME_OVER_HINT, 1.0), 6, 0xCCCCCC, 1.0);
+}
+
+/// Pause menu drawn over the paused game, showing either just the pause title or the build so far
+pub fn draw_pause_screen(game_state: &mut GameState, show_build: bool) {
+    update_pixel_buffer(game_state);
+    darken_window_buffer(game_state, 0.3);
+
+    let bit_font = BitFont { chars: get_font_data() };
+    if show_build {
+        draw_build_log(game_state);
+    } else {
+        bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, PAUSED, centered_text_x(PAUSED, 2.0), 90, 0xFFFFFF, 2.0);
+    }
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, PAUSE_HINT, centered_text_x(PAUSE_HINT, 1.0), 6, 0xCCCCCC, 1.0);
+}
+
+/// The arena frozen under the outcome of a versus round, `winner` being the index of the player who took it
+pub fn draw_round_over_screen(game_state: &mut GameState, winner: Option<usize>) {
+    update_pixel_buffer(game_state);
+    darken_window_buffer(game_state, 0.3);
+
+    let (text, color) = match winner {
+        Some(winner) => (ROUND_WINNER[winner], winner_color(game_state, winner)),
+        None => (ROUND_DRAW, 0xFFFFFF),
+    };
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, text, centered_text_x(text, 1.0), 100, color, 1.0);
+}
+
+/// The arena under the match result, with `hint` saying what comes next
+pub fn draw_match_over_screen(game_state: &mut GameState, winner: usize, hint: &str) {
+    update_pixel_buffer(game_state);
+    darken_window_buffer(game_state, 0.6);
+
+    let text = MATCH_WINNER[winner];
+    let color = winner_color(game_state, winner);
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, text, centered_text_x(text, 1.0), 100, color, 1.0);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, hint, centered_text_x(hint, 1.0), 6, 0xCCCCCC, 1.0);
+}
+
+/// The arena dimmed under a line about the connection to the other player, e.g. while waiting for them to join
+pub fn draw_net_status_screen(game_state: &mut GameState, text: &str, hint: &str) {
+    update_pixel_buffer(game_state);
+    darken_window_buffer(game_state, 0.4);
+
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, text, centered_text_x(text, 1.0), 100, 0xFFFFFF, 1.0);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, hint, centered_text_x(hint, 1.0), 6, 0xCCCCCC, 1.0);
+}
+
+fn winner_color(game_state: &GameState, winner: usize) -> u32 {
+    let skin = if winner == 0 { game_state.player.skin } else { game_state.rivals.first().map_or(0, |rival| rival.snake.skin) };
+    WORM_SKIN_TINTS[skin]
+}
+
+pub fn draw_title_screen(game_state: &mut GameState) {
+    draw_background(game_state);
+
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, TITLE, centered_text_x(TITLE, 2.0), 80, 0xFFFFFF, 2.0);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, TITLE_HINT, centered_text_x(TITLE_HINT, 1.0), 130, 0xCCCCCC, 1.0);
+
+    if let Some(best) = game_state.high_scores.first() {
+        let best_text = format!("{}{}", BEST, best.score);
+        bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, &best_text, centered_text_x(&best_text, 1.0), ART_HEIGHT as i32 - 32, 0xFFD700, 1.0);
+    }
+}
+
+/// The demo game as it is, marked so it isn't mistaken for a real run
+pub fn draw_demo_screen(game_state: &mut GameState) {
+    update_pixel_buffer(game_state);
+
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, DEMO, centered_text_x(DEMO, 2.0), 40, 0xFFFFFF, 2.0);
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, DEMO_HINT, centered_text_x(DEMO_HINT, 1.0), ART_HEIGHT as i32 - 20, 0xCCCCCC, 1.0);
+}
+
+/// Lists the perks taken this run in the order they were taken, with the score and run time of each pick.
+/// Only the latest picks are listed when they don't all fit on screen.
+fn draw_build_log(game_state: &mut GameState) {
+    let bit_font = BitFont { chars: get_font_data() };
+    bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, BUILD, centered_text_x(BUILD, 1.5), 22, 0xFFFFFF, 1.5);
+
+    if game_state.perk_history.is_empty() {
+        bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, NO_PERKS_TAKEN, centered_text_x(NO_PERKS_TAKEN, 1.0), 100, 0xCCCCCC, 1.0);
+        return;
+    }
+
+    let row_height = 10;
+    let max_rows = (ART_HEIGHT - 50) / row_height;
+    let skipped = game_state.perk_history.len().saturating_sub(max_rows);
+
+    for (i, pick) in game_state.perk_history.iter().skip(skipped).enumerate() {
+        let definition = perk_definition(pick.perk);
+        let seconds = pick.time as u32;
+        let row = format!("{:>6} {:<15} {}:{:02}", pick.score, definition.name, seconds / 60, seconds % 60);
+
+        bit_font.draw_text_smooth_scaled(
+            &mut game_state.window_buffer,
+            ART_WIDTH,
+            &row,
+            8,
+            (44 + i * row_height) as i32,
+            definition.rarity.color(),
+            1.0
+        );
+    }
+}
+
+/// Multiplies every pixel of the window buffer by `factor`, to put text on top of a busy screen
+fn darken_window_buffer(game_state: &mut GameState, factor: f32) {
+    for pixel in game_state.window_buffer.iter_mut() {
+        let r = (((*pixel >> 16) & 0xFF) as f32 * factor) as u32;
+        let g = (((*pixel >> 8) & 0xFF) as f32 * factor) as u32;
+        let b = ((*pixel & 0xFF) as f32 * factor) as u32;
+        *pixel = 0xFF000000 | (r << 16) | (g << 8) | b;
+    }
+}
+
+pub fn draw_choose_perk_screen_with_highlight(game_state: &mut GameState, offers: &[Perk], highlighted_perk: Option<usize>) {
 
     // Draw top part of the perk screen which prompts the user for selection
     draw_sprite(
         0,
         0,
         &game_state.sprites.choose_perk[0],
-        game_state.window_buffer,
+        &mut game_state.window_buffer,
         ART_WIDTH,
         None
     );
@@ -145,19 +469,24 @@ pub fn draw_choose_perk_screen_with_highlight(game_state: &mut GameState, highli
     // Draw the "Select perk" text at the top of the screen
     bit_font.draw_text_smooth_scaled(&mut game_state.window_buffer, ART_WIDTH, SELECT_PERK, 57, 25, 0xFFFFFF, 1.7); // White color
 
-    // Designate the bottom part of the perk screen which shows the two available perks
-    let perk_positions = [
-        (0, ART_HEIGHT / 2),
-        (128, ART_HEIGHT / 2),
-    ];
+    // The bottom half of the perk screen is split into one card slot per offered perk,
+    // with cards shrunk to fit when there are more than two
+    let slot_width = ART_WIDTH / offers.len().max(1);
+    let bottom_half = ART_HEIGHT / 2;
 
-    // Draw the perks at their designated positions with highlight added based on highlighted_perk
-    for (i, &(x, y)) in perk_positions.iter().enumerate() {
-        let perk_index = i + 1;
-        let is_highlighted = highlighted_perk == Some(perk_index);
-        let is_selected = game_state.selected_perk == Some(perk_index);
+    // Draw the offered perks in their slots with highlight added based on highlighted_perk
+    for (i, perk) in offers.iter().enumerate() {
+        let is_highlighted = highlighted_perk == Some(i);
+        let is_selected = game_state.selected_perk == Some(i);
+        let icon_frame = perk_definition(*perk).icon_frame;
+
+        if icon_frame < game_state.sprites.perks.len() {
+            let icon = &game_state.sprites.perks[icon_frame];
+            let card_width = slot_width.min(icon.width as usize);
+            let card_height = icon.height as usize * card_width / icon.width as usize;
+            let x = i * slot_width + (slot_width - card_width) / 2;
+            let y = bottom_half + (bottom_half - card_height) / 2;
 
-        if i < game_state.sprites.perks.len() {
             let darkness_factor = if is_selected {
                 None // Selected - full brightness
             } else if is_highlighted {
@@ -166,43 +495,55 @@ pub fn draw_choose_perk_screen_with_highlight(game_state: &mut GameState, highli
                 Some(0.5) // Normal - more dim
             };
 
-            draw_sprite(
+            draw_sprite_scaled(
                 x,
                 y,
-                &game_state.sprites.perks[i],
-                game_state.window_buffer,
+                icon,
+                card_width,
+                card_height,
+                &mut game_state.window_buffer,
                 ART_WIDTH,
                 darkness_factor,
             );
         }
     }
 
-    // Must also draw information about the perk which is highlighted
+    // Remind the player that the offer can be rerolled, greyed out when it can't be afforded
+    let reroll_text = format!("{}{}", REROLL_PERKS, PERK_REROLL_COST);
+    let reroll_color = if game_state.score >= PERK_REROLL_COST { 0xCCCCCC } else { 0x555555 };
+    bit_font.draw_text_smooth_scaled(
+        &mut game_state.window_buffer,
+        ART_WIDTH,
+        &reroll_text,
+        centered_text_x(&reroll_text, 1.0),
+        90, // Y position
+        reroll_color,
+        1.0 // Scale
+    );
 
-    if let Some(perk_index) = highlighted_perk {
-        let perk_info = match perk_index {
-            1 => PERK_NEED_4_SPEED,
-            2 => PERK_HUNGRY_WORM,
-            _ => PERK_CURSE_OF_GLOSSY,
-        };
+    // Must also draw information about the perk which is highlighted
 
-        // Draw the first line of perk information
+    if let Some(definition) = highlighted_perk
+        .and_then(|perk_index| offers.get(perk_index))
+        .map(|perk| perk_definition(*perk))
+    {
+        // Draw the name of the perk, colored by its rarity
         bit_font.draw_text_smooth_scaled(
             &mut game_state.window_buffer,
             ART_WIDTH,
-            perk_info.0,
-            75, // X position
+            definition.name,
+            centered_text_x(definition.name, 1.0),
             55, // Y position
-            0xFFD700, // Golden color
+            definition.rarity.color(),
             1.0 // Scale
         );
 
-        // Draw the second line of perk information
+        // Draw the description of the perk
         bit_font.draw_text_smooth_scaled(
             &mut game_state.window_buffer,
             ART_WIDTH,
-            perk_info.1,
-            52, // X position
+            definition.description,
+            centered_text_x(definition.description, 1.0),
             69, // Y position
             0xCCCCCC, // Slightly grey color
             1.0 // Scale
@@ -210,6 +551,12 @@ pub fn draw_choose_perk_screen_with_highlight(game_state: &mut GameState, highli
     }
 }
 
+/// X position which centers `text` on screen when drawn at the given scale
+fn centered_text_x(text: &str, scale: f32) -> i32 {
+    let text_width = (text.len() as f32 * 8.0 * scale) as i32;
+    (ART_WIDTH as i32 - text_width) / 2
+}
+
 
 pub fn draw_background(state: &mut GameState) {
 
@@ -218,7 +565,7 @@ pub fn draw_background(state: &mut GameState) {
         0,
         0,
         &state.sprites.blue_strip[0],
-        state.window_buffer,
+        &mut state.window_buffer,
         ART_WIDTH,
         None
     );
@@ -237,7 +584,7 @@ pub fn draw_background(state: &mut GameState) {
 
         // Increment the x offset for layer 0
         if i == 0 {
-            state.stars_offset_x = state.stars_offset_x + 1;
+            state.stars_offset_x += 1;
         }
 
         // Select the appropriate layer based on the index
@@ -253,7 +600,7 @@ pub fn draw_background(state: &mut GameState) {
                 offset_x,
                 offset_y,
                 layer,
-                state.window_buffer,
+                &mut state.window_buffer,
                 ART_WIDTH,
                 None
             );
@@ -262,7 +609,7 @@ pub fn draw_background(state: &mut GameState) {
                 offset_x,
                 offset_y,
                 layer,
-                state.window_buffer,
+                &mut state.window_buffer,
                 ART_WIDTH,
                 |_sprite_col, _sprite_row, world_x, _world_y| {
                     let art_width_f = ART_WIDTH as f32;
@@ -274,7 +621,7 @@ pub fn draw_background(state: &mut GameState) {
                         let shade_start = art_width_f / 1.9;
                         let shade_end = art_width_f / 1.7;
                         let progress = (x_f - shade_start) / (shade_end - shade_start);
-                        let progress = progress.min(1.0).max(0.0);
+                        let progress = progress.clamp(0.0, 1.0);
 
                         // Interpolate between 0.8 (light shade) and 0.6 (dark shade)
                         let darkness = 0.8 - (progress * 0.2);
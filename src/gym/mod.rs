@@ -0,0 +1,188 @@
+pub mod observation;
+pub mod protocol;
+pub mod tournament;
+
+use crate::ai::steer_ai_worms;
+use crate::audio::manager::AudioManager;
+use crate::graphics::sprites::SpriteMaps;
+use crate::gym::observation::{encode, Observation, ObservationEncoding};
+use crate::state::constants::gym::{DEATH_REWARD, HEADLESS_DELTA_TIME, MAX_FRAMES_PER_STEP};
+use crate::state::constants::state::{BASE_FOOD_SCORE, PLAYER_START};
+use crate::state::core::{execute_core_logic, initialize_headless_core_logic_map, CoreLogic};
+use crate::state::perks::{roll_perk_offers, take_perk};
+use crate::state::structs::{Direction, GameState, Perk, Snake};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Space Worm as a reinforcement learning environment: no window, no sound, and the same core logic as the game.
+/// Every step lasts until the worm has moved once, with simulation time advancing a fixed amount per frame
+/// so episodes replay exactly from their seed. A perk pick earned during a step is offered once the step is over,
+/// and goes to the first perk offered unless `choose_perk` picks another before the next step.
+pub struct Environment {
+    game_state: GameState<'static>,
+    core_logic: Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)>,
+    encoding: ObservationEncoding,
+    perk_offers: Vec<Perk>,
+}
+
+impl Environment {
+    /// Only the `Framebuffer` encoding draws the game, so it is the only one that loads sprites from `assets/`;
+    /// the others read no files at all
+    pub fn new(encoding: ObservationEncoding) -> Self {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        let sprites = match encoding {
+            ObservationEncoding::Framebuffer => SpriteMaps::new(),
+            ObservationEncoding::Grid | ObservationEncoding::Features => SpriteMaps::empty(),
+        };
+        let mut game_state = GameState::new(player, sprites, 0, 0, None, AudioManager::silent(), Vec::new(), 0);
+        game_state.fixed_delta_time = Some(HEADLESS_DELTA_TIME);
+
+        Environment {
+            game_state,
+            core_logic: initialize_headless_core_logic_map(),
+            encoding,
+            perk_offers: Vec::new(),
+        }
+    }
+
+    /// Starts a new episode, which plays out the same way every time for the same seed and actions
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game_state.rng = StdRng::seed_from_u64(seed);
+        self.game_state.restart_level();
+        self.game_state.delta_time = 0.0;
+        self.game_state.last_frame_time = None;
+        self.perk_offers.clear();
+        self.observe()
+    }
+
+    /// Turns the worm towards `action`, or keeps it going straight for `None`, and plays until it has moved.
+    /// The reward is the score earned, in units of plain food, or `DEATH_REWARD` if the worm died.
+    pub fn step(&mut self, action: Option<Direction>) -> (Observation, f32, bool) {
+        if self.game_state.game_over {
+            return (self.observe(), 0.0, true);
+        }
+
+        if let Some(direction) = action {
+            self.game_state.player.direction = direction;
+        }
+
+        // Whatever is still on offer wasn't chosen, so it goes to the first perk
+        while !self.perk_offers.is_empty() {
+            self.choose_perk(0);
+        }
+
+        let score = self.game_state.score;
+        let moves = self.game_state.player.moves;
+        for _ in 0..MAX_FRAMES_PER_STEP {
+            steer_ai_worms(&mut self.game_state);
+            execute_core_logic(&mut self.game_state, &self.core_logic);
+
+            if self.game_state.game_over || self.game_state.player.moves != moves {
+                break;
+            }
+        }
+
+        if !self.game_state.game_over {
+            self.offer_next_perks();
+        }
+
+        let done = self.game_state.game_over;
+        let reward = if done { DEATH_REWARD } else { self.game_state.score.saturating_sub(score) as f32 / BASE_FOOD_SCORE as f32 };
+        (self.observe(), reward, done)
+    }
+
+    pub fn observe(&mut self) -> Observation {
+        encode(&mut self.game_state, self.encoding)
+    }
+
+    pub fn game_state(&self) -> &GameState<'static> {
+        &self.game_state
+    }
+
+    /// For setting up episodes beyond what `reset` does, e.g. adding computer-controlled rivals
+    pub fn game_state_mut(&mut self) -> &mut GameState<'static> {
+        &mut self.game_state
+    }
+
+    /// The perks to choose from for a pick earned during the last step, empty when there is no pick to make
+    pub fn perk_offers(&self) -> &[Perk] {
+        &self.perk_offers
+    }
+
+    /// Takes the offered perk at `index`, then offers the next queued pick if there is one.
+    /// An index past the end of the offer takes nothing.
+    pub fn choose_perk(&mut self, index: usize) {
+        if self.perk_offers.is_empty() {
+            return;
+        }
+
+        if let Some(&perk) = self.perk_offers.get(index) {
+            take_perk(&mut self.game_state, perk);
+        }
+        self.perk_offers.clear();
+        self.offer_next_perks();
+    }
+
+    fn offer_next_perks(&mut self) {
+        while self.perk_offers.is_empty() && self.game_state.pending_perk_picks > 0 {
+            self.game_state.pending_perk_picks -= 1;
+
+            let taken: Vec<Perk> = self.game_state.perk_history.iter().map(|pick| pick.perk).collect();
+            self.perk_offers = roll_perk_offers(&taken, self.game_state.perk_offer_count, &mut self.game_state.rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::controller::{ArenaView, WormController};
+    use crate::ai::greedy::GreedyController;
+    use crate::state::structs::WormId;
+
+    const MAX_STEPS: usize = 300;
+
+    /// Plays an episode with the greedy controller picking every action, and returns the actions it took
+    fn play_greedily(environment: &mut Environment, seed: u64) -> Vec<Option<Direction>> {
+        let mut controller = GreedyController { lookahead: 8 };
+        let mut actions = Vec::new();
+        environment.reset(seed);
+        for _ in 0..MAX_STEPS {
+            let action = Some(controller.choose_direction(&ArenaView::new(environment.game_state(), WormId::Player)));
+            actions.push(action);
+            if environment.step(action).2 {
+                break;
+            }
+        }
+        actions
+    }
+
+    fn replay(environment: &mut Environment, seed: u64, actions: &[Option<Direction>]) -> Vec<(Observation, f32, bool)> {
+        let mut transitions = vec![(environment.reset(seed), 0.0, false)];
+        transitions.extend(actions.iter().map(|&action| environment.step(action)));
+        transitions
+    }
+
+    #[test]
+    fn the_same_seed_and_actions_replay_the_same_episode() {
+        for encoding in [ObservationEncoding::Grid, ObservationEncoding::Features] {
+            let mut environment = Environment::new(encoding);
+            let actions = play_greedily(&mut environment, 7);
+            let first = replay(&mut environment, 7, &actions);
+            let second = replay(&mut Environment::new(encoding), 7, &actions);
+
+            assert!(first.iter().any(|&(_, reward, _)| reward > 0.0), "the episode should eat something");
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn different_seeds_play_out_differently() {
+        let mut environment = Environment::new(ObservationEncoding::Features);
+        let actions = play_greedily(&mut environment, 7);
+
+        assert_ne!(replay(&mut environment, 7, &actions), replay(&mut environment, 8, &actions));
+    }
+}
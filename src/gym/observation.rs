@@ -0,0 +1,116 @@
+use crate::ai::controller::{next_position, ArenaView, DIRECTIONS};
+use crate::graphics::update::update_pixel_buffer;
+use crate::state::constants::graphics::{ART_HEIGHT, ART_WIDTH, SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
+use crate::state::constants::gym::{FEATURE_COUNT, GRID_CHANNELS, GRID_HEIGHT, GRID_WIDTH};
+use crate::state::constants::physics::{LOWER_BOUND_X, LOWER_BOUND_Y, UPPER_BOUND_X, UPPER_BOUND_Y};
+use crate::state::constants::state::MAX_COMBO;
+use crate::state::structs::{GameState, Vector2D, WormId};
+
+/// How an environment describes the game to an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservationEncoding {
+    Grid,
+    Framebuffer,
+    Features,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Observation {
+    /// `GRID_CHANNELS` planes of `GRID_WIDTH` x `GRID_HEIGHT` cells, plane by plane and row by row, each cell 0.0 or 1.0.
+    /// The planes are: own head, own body, other worms, food, hazards.
+    Grid(Vec<f32>),
+    /// The `ART_WIDTH` x `ART_HEIGHT` picture the player would see, row by row, as 0xRRGGBB
+    Framebuffer(Vec<u32>),
+    /// `FEATURE_COUNT` numbers, see `encode_features`
+    Features(Vec<f32>),
+}
+
+pub fn encode(game_state: &mut GameState, encoding: ObservationEncoding) -> Observation {
+    match encoding {
+        ObservationEncoding::Grid => Observation::Grid(encode_grid(game_state)),
+        ObservationEncoding::Framebuffer => Observation::Framebuffer(encode_framebuffer(game_state)),
+        ObservationEncoding::Features => Observation::Features(encode_features(game_state)),
+    }
+}
+
+fn encode_grid(game_state: &GameState) -> Vec<f32> {
+    let mut grid = vec![0.0; GRID_CHANNELS * GRID_WIDTH * GRID_HEIGHT];
+    let mut mark = |channel: usize, position: Vector2D| {
+        let (column, row) = grid_cell(position);
+        grid[(channel * GRID_HEIGHT + row) * GRID_WIDTH + column] = 1.0;
+    };
+
+    mark(0, game_state.player.body[0]);
+    for &segment in game_state.player.body[1..].iter() {
+        mark(1, segment);
+    }
+    for rival in game_state.rivals.iter().filter(|rival| rival.alive) {
+        for &segment in rival.snake.body.iter() {
+            mark(2, segment);
+        }
+    }
+    for food in game_state.foods.iter() {
+        mark(3, food.position);
+    }
+    for hazard in game_state.hazards.iter() {
+        mark(4, hazard.position);
+    }
+
+    grid
+}
+
+fn grid_cell(position: Vector2D) -> (usize, usize) {
+    let column = ((position.x - LOWER_BOUND_X) / SNAKE_BODY_WIDTH).floor().clamp(0.0, (GRID_WIDTH - 1) as f32);
+    let row = ((position.y - LOWER_BOUND_Y) / SNAKE_BODY_HEIGHT).floor().clamp(0.0, (GRID_HEIGHT - 1) as f32);
+    (column as usize, row as usize)
+}
+
+/// Draws the frame with the game's own renderer
+fn encode_framebuffer(game_state: &mut GameState) -> Vec<u32> {
+    update_pixel_buffer(game_state);
+    game_state.window_buffer[..ART_WIDTH * ART_HEIGHT].iter().map(|pixel| pixel & 0xFFFFFF).collect()
+}
+
+/// In order:
+/// - head x and y, 0.0 to 1.0 across the arena
+/// - heading, one-hot over up, down, left, right
+/// - whether a step up, down, left or right would crash, 1.0 if so
+/// - x and y offset to the closest food the short way round the arena, -1.0 to 1.0
+/// - length in segments over 100
+/// - combo over its maximum
+fn encode_features(game_state: &GameState) -> Vec<f32> {
+    let arena = ArenaView::new(game_state, WormId::Player);
+    let head = arena.head();
+    let (width, height) = (UPPER_BOUND_X - LOWER_BOUND_X, UPPER_BOUND_Y - LOWER_BOUND_Y);
+
+    let mut features = Vec::with_capacity(FEATURE_COUNT);
+    features.push((head.x - LOWER_BOUND_X) / width);
+    features.push((head.y - LOWER_BOUND_Y) / height);
+    features.extend(DIRECTIONS.iter().map(|&direction| if arena.worm.direction == direction { 1.0 } else { 0.0 }));
+    features.extend(DIRECTIONS.iter().map(|&direction| if arena.is_blocked(next_position(head, direction), 1) { 1.0 } else { 0.0 }));
+
+    let closest_food = game_state
+        .foods
+        .iter()
+        .map(|food| (wrapped_offset(head.x, food.position.x, width), wrapped_offset(head.y, food.position.y, height)))
+        .min_by(|a, b| (a.0.abs() + a.1.abs()).total_cmp(&(b.0.abs() + b.1.abs())))
+        .unwrap_or((0.0, 0.0));
+    features.push(closest_food.0 / (width / 2.0));
+    features.push(closest_food.1 / (height / 2.0));
+
+    features.push(game_state.player.body.len() as f32 / 100.0);
+    features.push(game_state.combo as f32 / MAX_COMBO as f32);
+    features
+}
+
+/// Signed distance from `from` to `to`, going whichever way round the wrapping arena is shorter
+fn wrapped_offset(from: f32, to: f32, span: f32) -> f32 {
+    let offset = to - from;
+    if offset > span / 2.0 {
+        offset - span
+    } else if offset < -span / 2.0 {
+        offset + span
+    } else {
+        offset
+    }
+}
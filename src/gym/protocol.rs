@@ -0,0 +1,193 @@
+use crate::ai::{ControllerKind, Difficulty};
+use crate::gym::observation::{Observation, ObservationEncoding};
+use crate::gym::Environment;
+use crate::state::structs::{Direction, Vector2D};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Everything a bot gets to see after each step, written as one line of JSON
+#[derive(Serialize)]
+struct TickMessage {
+    tick: u64,
+    /// Head first
+    body: Vec<[f32; 2]>,
+    direction: &'static str,
+    food: Vec<FoodMessage>,
+    hazards: Vec<[f32; 2]>,
+    /// Bodies of the computer-controlled rivals still in play, head first
+    rivals: Vec<Vec<[f32; 2]>>,
+    score: u32,
+    /// Perks taken so far, in the order they were taken
+    perks: Vec<String>,
+    /// Perks to choose from with the next action, empty when there is no pick to make
+    perk_offers: Vec<String>,
+    game_over: bool,
+}
+
+#[derive(Serialize)]
+struct FoodMessage {
+    kind: String,
+    x: f32,
+    y: f32,
+}
+
+#[derive(Serialize)]
+struct ErrorMessage {
+    error: String,
+}
+
+/// A bot's move, one line of JSON such as `{"direction": "up", "perk": 1}`. Both fields may be left out:
+/// no direction keeps the worm going straight, no perk takes the first one on offer.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ActionMessage {
+    direction: Option<String>,
+    perk: Option<usize>,
+}
+
+/// Plays a headless game driven by a bot over JSON lines: the state before the first move, then one action read and
+/// one state written per step until the game is over or `input` runs out. Lines that aren't a valid action are
+/// answered with an `{"error": ...}` line and don't advance the game.
+pub fn run_bot_protocol(
+    seed: u64,
+    rivals: &[(ControllerKind, Difficulty)],
+    input: impl BufRead,
+    mut output: impl Write,
+) -> std::io::Result<()> {
+    let mut environment = Environment::new(ObservationEncoding::Features);
+    for &(kind, difficulty) in rivals {
+        environment.game_state_mut().add_ai_rival(kind, difficulty);
+    }
+    environment.reset(seed);
+
+    let mut tick = 0;
+    write_line(&mut output, &tick_message(&environment, tick))?;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let action = match parse_action(&line) {
+            Ok(action) => action,
+            Err(error) => {
+                write_line(&mut output, &ErrorMessage { error })?;
+                continue;
+            }
+        };
+
+        let (_, _, done) = apply_action(&mut environment, action);
+        tick += 1;
+        write_line(&mut output, &tick_message(&environment, tick))?;
+
+        if done {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// A bot playing as a child process over the same protocol, with this side running the game
+pub struct BotProcess {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl BotProcess {
+    /// Starts `command`, split on whitespace into the program and its arguments
+    pub fn spawn(command: &str) -> std::io::Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Empty bot command"))?;
+        let mut child = Command::new(program).args(parts).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().expect("Bot stdout is piped"));
+        Ok(BotProcess { child, stdin, stdout })
+    }
+
+    /// Shows the bot the state of the game and plays the action it answers with
+    pub fn play_step(&mut self, environment: &mut Environment, tick: u64) -> std::io::Result<bool> {
+        let stdin = self.stdin.as_mut().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Bot is finished"))?;
+        write_line(stdin, &tick_message(environment, tick))?;
+
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Bot quit mid-game"));
+        }
+        let action = parse_action(&line).map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+
+        let (_, _, done) = apply_action(environment, action);
+        Ok(done)
+    }
+
+    /// Shows the bot how the game ended, then waits for it to exit
+    pub fn finish(mut self, environment: &Environment, tick: u64) -> std::io::Result<()> {
+        if let Some(mut stdin) = self.stdin.take() {
+            // A bot may well exit as soon as it sees the game is over, so it missing the last line is fine
+            let _ = write_line(&mut stdin, &tick_message(environment, tick));
+        }
+        self.child.wait()?;
+        Ok(())
+    }
+}
+
+fn apply_action(environment: &mut Environment, (direction, perk): (Option<Direction>, Option<usize>)) -> (Observation, f32, bool) {
+    if let Some(index) = perk {
+        environment.choose_perk(index);
+    }
+    environment.step(direction)
+}
+
+fn parse_action(line: &str) -> Result<(Option<Direction>, Option<usize>), String> {
+    let action: ActionMessage = serde_json::from_str(line).map_err(|e| format!("Invalid action: {}", e))?;
+    let direction = match action.direction.as_deref() {
+        None => None,
+        Some("up") => Some(Direction::Up),
+        Some("down") => Some(Direction::Down),
+        Some("left") => Some(Direction::Left),
+        Some("right") => Some(Direction::Right),
+        Some(other) => return Err(format!("Unknown direction: {}", other)),
+    };
+    Ok((direction, action.perk))
+}
+
+fn tick_message(environment: &Environment, tick: u64) -> TickMessage {
+    let game_state = environment.game_state();
+    let point = |position: &Vector2D| [position.x, position.y];
+
+    TickMessage {
+        tick,
+        body: game_state.player.body.iter().map(point).collect(),
+        direction: direction_name(game_state.player.direction),
+        food: game_state
+            .foods
+            .iter()
+            .map(|food| FoodMessage { kind: format!("{:?}", food.kind), x: food.position.x, y: food.position.y })
+            .collect(),
+        hazards: game_state.hazards.iter().map(|hazard| point(&hazard.position)).collect(),
+        rivals: game_state.rivals.iter().filter(|rival| rival.alive).map(|rival| rival.snake.body.iter().map(point).collect()).collect(),
+        score: game_state.score,
+        perks: game_state.perk_history.iter().map(|pick| format!("{:?}", pick.perk)).collect(),
+        perk_offers: environment.perk_offers().iter().map(|perk| format!("{:?}", perk)).collect(),
+        game_over: game_state.game_over,
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+fn write_line(output: &mut impl Write, message: &impl Serialize) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *output, message)?;
+    writeln!(output)?;
+    output.flush()
+}
@@ -0,0 +1,187 @@
+use crate::ai::{AiPilot, ControllerKind, Difficulty};
+use crate::gym::protocol::BotProcess;
+use crate::gym::Environment;
+use crate::state::structs::DeathCause;
+use serde::Serialize;
+
+/// Something that can play a tournament game
+#[derive(Debug, Clone, PartialEq)]
+pub enum Contestant {
+    Controller(ControllerKind),
+    /// Command line of a program speaking the bot protocol
+    External(String),
+}
+
+impl Contestant {
+    pub fn name(&self) -> String {
+        match self {
+            Contestant::Controller(kind) => format!("{:?}", kind),
+            Contestant::External(command) => command.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GameResult {
+    pub seed: u64,
+    pub score: u32,
+    /// Simulation seconds the worm stayed alive
+    pub survival_time: f32,
+    /// `None` if the worm was still alive when the game was cut off
+    pub death_cause: Option<DeathCause>,
+}
+
+/// How a contestant did over all of its games
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub contestant: String,
+    pub games: usize,
+    pub mean_score: f32,
+    pub median_score: f32,
+    pub mean_survival_time: f32,
+    pub median_survival_time: f32,
+    pub self_collisions: usize,
+    pub worm_collisions: usize,
+    pub hazard_deaths: usize,
+    /// Games cut off after the step limit with the worm still alive
+    pub survived: usize,
+    pub results: Vec<GameResult>,
+}
+
+/// Plays one game with `seed`, stopping after `max_steps` worm moves if the worm is still alive by then
+pub fn play_game(
+    environment: &mut Environment,
+    contestant: &Contestant,
+    difficulty: Difficulty,
+    seed: u64,
+    max_steps: u64,
+) -> std::io::Result<GameResult> {
+    environment.reset(seed);
+
+    match contestant {
+        Contestant::Controller(kind) => {
+            // Steered the same way as the demo, reaction delay and all
+            environment.game_state_mut().autopilot = Some(AiPilot::new(*kind, difficulty));
+            for _ in 0..max_steps {
+                let (_, _, done) = environment.step(None);
+                if done {
+                    break;
+                }
+            }
+            environment.game_state_mut().autopilot = None;
+        }
+        Contestant::External(command) => {
+            let mut bot = BotProcess::spawn(command)?;
+            let mut tick = 0;
+            while tick < max_steps {
+                let done = bot.play_step(environment, tick)?;
+                tick += 1;
+                if done {
+                    break;
+                }
+            }
+            bot.finish(environment, tick)?;
+        }
+    }
+
+    let game_state = environment.game_state();
+    Ok(GameResult {
+        seed,
+        score: game_state.score,
+        survival_time: game_state.run_time,
+        death_cause: game_state.death_cause,
+    })
+}
+
+pub fn summarize(contestant: &Contestant, results: Vec<GameResult>) -> Summary {
+    let deaths = |cause: DeathCause| results.iter().filter(|result| result.death_cause == Some(cause)).count();
+    let scores: Vec<f32> = results.iter().map(|result| result.score as f32).collect();
+    let survival_times: Vec<f32> = results.iter().map(|result| result.survival_time).collect();
+
+    Summary {
+        contestant: contestant.name(),
+        games: results.len(),
+        mean_score: mean(&scores),
+        median_score: median(&scores),
+        mean_survival_time: mean(&survival_times),
+        median_survival_time: median(&survival_times),
+        self_collisions: deaths(DeathCause::SelfCollision),
+        worm_collisions: deaths(DeathCause::WormCollision),
+        hazard_deaths: deaths(DeathCause::Hazard),
+        survived: results.iter().filter(|result| result.death_cause.is_none()).count(),
+        results,
+    }
+}
+
+fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() { 0.0 } else { values.iter().sum::<f32>() / values.len() as f32 }
+}
+
+fn median(values: &[f32]) -> f32 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f32::total_cmp);
+
+    match sorted.len() {
+        0 => 0.0,
+        length if length % 2 == 0 => (sorted[length / 2 - 1] + sorted[length / 2]) / 2.0,
+        length => sorted[length / 2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ControllerKind;
+
+    fn game(score: u32, survival_time: f32, death_cause: Option<DeathCause>) -> GameResult {
+        GameResult { seed: 0, score, survival_time, death_cause }
+    }
+
+    #[test]
+    fn median_of_an_odd_count_is_the_middle_value() {
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert_eq!(median(&[4.0]), 4.0);
+    }
+
+    #[test]
+    fn median_of_an_even_count_averages_the_middle_two() {
+        assert_eq!(median(&[8.0, 1.0, 4.0, 2.0]), 3.0);
+        assert_eq!(median(&[1.0, 2.0]), 1.5);
+    }
+
+    #[test]
+    fn nothing_to_summarize_comes_out_as_zeros() {
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(median(&[]), 0.0);
+
+        let summary = summarize(&Contestant::Controller(ControllerKind::Greedy), Vec::new());
+        assert_eq!(summary.games, 0);
+        assert_eq!(summary.mean_score, 0.0);
+        assert_eq!(summary.median_survival_time, 0.0);
+        assert_eq!(summary.survived, 0);
+    }
+
+    #[test]
+    fn summary_averages_the_games_and_counts_each_way_of_dying() {
+        let results = vec![
+            game(100, 10.0, Some(DeathCause::SelfCollision)),
+            game(400, 40.0, Some(DeathCause::WormCollision)),
+            game(200, 20.0, Some(DeathCause::SelfCollision)),
+            game(900, 90.0, None),
+            game(300, 30.0, Some(DeathCause::Hazard)),
+        ];
+
+        let summary = summarize(&Contestant::External("./bot".to_string()), results);
+        assert_eq!(summary.contestant, "./bot");
+        assert_eq!(summary.games, 5);
+        assert_eq!(summary.mean_score, 380.0);
+        assert_eq!(summary.median_score, 300.0);
+        assert_eq!(summary.mean_survival_time, 38.0);
+        assert_eq!(summary.median_survival_time, 30.0);
+        assert_eq!(summary.self_collisions, 2);
+        assert_eq!(summary.worm_collisions, 1);
+        assert_eq!(summary.hazard_deaths, 1);
+        assert_eq!(summary.survived, 1);
+        assert_eq!(summary.results.len(), 5);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::state::structs::{Direction, GameState};
+use crate::state::structs::{Direction, GameState, RivalControl};
 use minifb::{Key, KeyRepeat};
 
 pub fn handle_user_input(game_state: &mut GameState) {
@@ -10,9 +10,26 @@ pub fn handle_user_input(game_state: &mut GameState) {
     ];
 
     for (key, direction) in key_direction_map.iter() {
-        if game_state.window.is_key_pressed(*key, KeyRepeat::Yes) {
+        if game_state.is_key_pressed(*key, KeyRepeat::Yes) {
             game_state.player.direction = *direction;
             break; // Only process first pressed key
         }
     }
+
+    // The arrow keys steer whichever rival is played from the same keyboard
+    let arrow_direction_map = [
+        (Key::Up, Direction::Up),
+        (Key::Left, Direction::Left),
+        (Key::Down, Direction::Down),
+        (Key::Right, Direction::Right),
+    ];
+
+    for (key, direction) in arrow_direction_map.iter() {
+        if game_state.is_key_pressed(*key, KeyRepeat::Yes) {
+            for rival in game_state.rivals.iter_mut().filter(|rival| matches!(rival.control, RivalControl::Keyboard)) {
+                rival.snake.direction = *direction;
+            }
+            break;
+        }
+    }
 }
\ No newline at end of file
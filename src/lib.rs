@@ -0,0 +1,7 @@
+pub mod ai;
+pub mod audio;
+pub mod graphics;
+pub mod gym;
+pub mod input;
+pub mod net;
+pub mod state;
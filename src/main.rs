@@ -2,29 +2,148 @@ use minifb::{Window, WindowOptions};
 use winit::event_loop::EventLoop;
 use winit::monitor::MonitorHandle;
 
-use crate::state::constants::graphics::{ART_WIDTH, SCALED_WINDOW_HEIGHT, SCALED_WINDOW_WIDTH};
+use space_worm::state::constants::graphics::{SCALED_WINDOW_HEIGHT, SCALED_WINDOW_WIDTH};
+use space_worm::state::constants::net::{DEFAULT_INPUT_DELAY, HARNESS_FRAMES, HARNESS_LATENCY_MS, ROLLBACK_INPUT_DELAY};
+use space_worm::state::constants::state::{PLAYER_START, VERSUS_ROUNDS_TO_WIN};
 
-use crate::state::structs::{Direction, GameState, Snake};
-use crate::{
+use space_worm::state::highscores::load_high_scores;
+use space_worm::state::structs::{Direction, GameState, Snake};
+use space_worm::{
     graphics::sprites::SpriteMaps,
     state::core::initialize_core_logic_map,
     state::r#loop::start_event_loop,
 };
 
-use rodio::{OutputStream, Sink};
-use crate::audio::manager::AudioManager;
-
-mod state;
-mod graphics;
-mod input;
-mod audio;
+use space_worm::ai::{ControllerKind, Difficulty};
+use space_worm::audio::director::Scene;
+use space_worm::audio::manager::AudioManager;
+use space_worm::audio::preview::run_sfx_preview;
+use space_worm::gym::protocol::run_bot_protocol;
+use space_worm::net::harness::{run_rollback_harness, HarnessConfig, HarnessReport, LinkConditions};
+use space_worm::net::online::{play_headless_online_versus, start_online_versus, NetRole};
+use space_worm::net::session::MatchSettings;
 
 fn main() {
-    // Initialize the audio output stream and sink
-    let (_stream, stream_handle) = OutputStream::try_default().unwrap();
+    // Tweak synthesized sound effects instead of starting the game
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--sfx-preview") {
+        if let Err(e) = run_sfx_preview(args.get(2).cloned()) {
+            eprintln!("SFX preview failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // A fixed seed makes food placement and perk offers repeat from run to run
+    let seed = args
+        .iter()
+        .position(|arg| arg == "--seed")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|seed| seed.parse::<u64>().ok())
+        .unwrap_or_else(rand::random);
+
+    // Two players on one keyboard, optionally followed by how many round wins take the match
+    let versus = args.iter().position(|arg| arg == "--versus").map(|index| {
+        args.get(index + 1).and_then(|rounds| rounds.parse::<u32>().ok()).filter(|&rounds| rounds > 0).unwrap_or(VERSUS_ROUNDS_TO_WIN)
+    });
+
+    // Computer-controlled rivals, one per `--rival greedy|astar`, all as sharp as `--difficulty easy|normal|hard`
+    let difficulty = args
+        .iter()
+        .position(|arg| arg == "--difficulty")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|name| Difficulty::from_name(name))
+        .unwrap_or(Difficulty::NORMAL);
+    let rival_kinds: Vec<ControllerKind> = args
+        .windows(2)
+        .filter(|pair| pair[0] == "--rival")
+        .filter_map(|pair| {
+            let kind = ControllerKind::from_name(&pair[1]);
+            if kind.is_none() {
+                eprintln!("Unknown rival controller: {}", pair[1]);
+            }
+            kind
+        })
+        .collect();
+
+    // An external bot plays a headless game over stdin and stdout instead
+    if args.iter().any(|arg| arg == "--bot-protocol") {
+        let rivals: Vec<(ControllerKind, Difficulty)> = rival_kinds.iter().map(|&kind| (kind, difficulty)).collect();
+        if let Err(e) = run_bot_protocol(seed, &rivals, std::io::stdin().lock(), std::io::stdout().lock()) {
+            eprintln!("Bot protocol failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Versus against someone on another machine: `--host PORT` waits for them, `--connect ADDRESS:PORT` joins a host.
+    // The host's `--seed`, `--versus` rounds, `--input-delay` ticks and `--lockstep` choice count for both.
+    let rollback = !args.iter().any(|arg| arg == "--lockstep");
+    let input_delay = args
+        .iter()
+        .position(|arg| arg == "--input-delay")
+        .and_then(|index| args.get(index + 1))
+        .and_then(|ticks| ticks.parse::<u64>().ok())
+        .unwrap_or(if rollback { ROLLBACK_INPUT_DELAY } else { DEFAULT_INPUT_DELAY });
+
+    // Two in-process peers playing online versus over a simulated connection, e.g. `--latency 80 --loss 5`
+    if args.iter().any(|arg| arg == "--rollback-harness") {
+        let number = |flag: &str| args.iter().position(|arg| arg == flag).and_then(|index| args.get(index + 1)).and_then(|value| value.parse::<f64>().ok());
+        let config = HarnessConfig {
+            conditions: LinkConditions {
+                latency_ms: number("--latency").map_or(HARNESS_LATENCY_MS, |ms| ms as u64),
+                jitter_ms: number("--jitter").map_or(0, |ms| ms as u64),
+                loss: number("--loss").map_or(0.0, |percent| (percent / 100.0).clamp(0.0, 1.0)),
+            },
+            seed,
+            frames: number("--frames").map_or(HARNESS_FRAMES, |frames| frames as u64),
+            input_delay,
+        };
+        print_harness_report(&config, &run_rollback_harness(&config));
+        return;
+    }
+
+    let net_role = if let Some(index) = args.iter().position(|arg| arg == "--host") {
+        let Some(port) = args.get(index + 1).and_then(|port| port.parse::<u16>().ok()) else {
+            eprintln!("--host needs a port to listen on");
+            std::process::exit(1);
+        };
+        let rounds_to_win = versus.unwrap_or(VERSUS_ROUNDS_TO_WIN);
+        Some(NetRole::Host { port, settings: MatchSettings { seed, rounds_to_win, input_delay, rollback } })
+    } else if let Some(index) = args.iter().position(|arg| arg == "--connect") {
+        let Some(address) = args.get(index + 1) else {
+            eprintln!("--connect needs the host's address, e.g. 192.168.1.20:7777");
+            std::process::exit(1);
+        };
+        Some(NetRole::Join { address: address.clone() })
+    } else {
+        None
+    };
+
+    // `--headless` plays the online match with no window or sound, the greedy controller steering,
+    // for `--frames` ticks, e.g. on a server or in a test
+    if let Some(role) = net_role.as_ref().filter(|_| args.iter().any(|arg| arg == "--headless")) {
+        let frames = args
+            .iter()
+            .position(|arg| arg == "--frames")
+            .and_then(|index| args.get(index + 1))
+            .and_then(|frames| frames.parse::<u64>().ok())
+            .unwrap_or(HARNESS_FRAMES);
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        let game_state = GameState::new(player, SpriteMaps::empty(), 0, 0, None, AudioManager::silent(), Vec::new(), seed);
+        match play_headless_online_versus(game_state, role.clone(), ControllerKind::Greedy.create(difficulty), frames) {
+            Ok(tick) => println!("In sync at tick {}", tick),
+            Err(e) => {
+                eprintln!("Online match failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let sprites = SpriteMaps::new();
 
-    let player = Snake::new(40.0, 150.0, Direction::Right);
+    let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
     let core_logic = initialize_core_logic_map();
     let fullscreen = false;
 
@@ -48,28 +167,73 @@ fn main() {
     });
 
 
-    // Initialize window and scaled buffer
-    let mut window_buffer = vec![0; ART_WIDTH * ART_WIDTH];
-    let mut scaled_buffer = vec![0; window_width * window_height];
+    // Create audio manager, which owns the output stream and waits for it to be ready, then preload audio files
+    let audio_manager = AudioManager::new();
+    if let Err(e) = audio_manager.preload_all_music() {
+        eprintln!("Warning: Failed to preload music: {}", e);
+    }
+    if let Err(e) = audio_manager.preload_all_sfx() {
+        eprintln!("Warning: Failed to preload sound effects: {}", e);
+    }
+    audio_manager.set_stereo_panning(!args.iter().any(|arg| arg == "--mono"));
+    audio_manager.set_shuffle(Scene::Gameplay, args.iter().any(|arg| arg == "--shuffle"));
 
-    // Create audio manager and preload audio files
-    let audio_manager = AudioManager::new().unwrap();
-    audio_manager.preload_all_music().unwrap();
-    audio_manager.preload_all_sfx().unwrap();
-
-    let game_state = GameState::new(
+    let mut game_state = GameState::new(
         player,
         sprites,
-        &mut window_buffer,
         window_width,
         window_height,
-        &mut window,
-        &mut scaled_buffer,
+        Some(&mut window),
         audio_manager,
+        load_high_scores(),
+        seed,
     );
+    if let Some(role) = net_role {
+        if let Err(e) = start_online_versus(game_state, role) {
+            eprintln!("Online match failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    // Sleep for a second just to allow the audio manager to initialize properly
-    std::thread::sleep(std::time::Duration::from_secs(1));
+    for kind in rival_kinds {
+        game_state.add_ai_rival(kind, difficulty);
+    }
+    if let Some(rounds_to_win) = versus {
+        game_state.start_versus(rounds_to_win);
+    } else {
+        game_state.restart_level();
+    }
 
     start_event_loop(game_state, core_logic);
+}
+
+fn print_harness_report(config: &HarnessConfig, report: &HarnessReport) {
+    let conditions = config.conditions;
+    println!(
+        "{} frames at {} ms latency, {} ms jitter, {:.1}% loss, {} tick input delay",
+        config.frames, conditions.latency_ms, conditions.jitter_ms, conditions.loss * 100.0, config.input_delay
+    );
+    for (index, name) in ["Host", "Client"].iter().enumerate() {
+        let stats = report.stats[index];
+        println!(
+            "{:<6} tick {:>6}  confirmed {:>6}  rounds {:>3}  rollbacks {:>5}  resimulated {:>6}  longest {:>3}  stalled {:>5}  hashes checked {:>4}",
+            name,
+            report.ticks[index],
+            report.confirmed_ticks[index],
+            report.rounds_played[index],
+            stats.rollbacks,
+            stats.resimulated_ticks,
+            stats.longest_rollback,
+            stats.stalled_frames,
+            stats.hashes_checked
+        );
+    }
+    match report.outcome {
+        Ok(()) => println!("In sync at tick {}", report.final_tick),
+        Err(error) => {
+            println!("Failed: {:?}", error);
+            std::process::exit(1);
+        }
+    }
 }
\ No newline at end of file
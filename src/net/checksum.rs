@@ -0,0 +1,132 @@
+use crate::state::structs::{GameState, Snake};
+use rand::RngCore;
+use std::hash::{Hash, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, whose algorithm the standard library is free to change between releases,
+/// it hashes the same bytes the same way in every build, so two peers built with different compilers still agree.
+/// Numbers are fed in little-endian and sizes as 64 bits, so the hash doesn't depend on the machine either.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Fingerprint of everything the simulation carries from one tick to the next. Two peers running the same game
+/// get the same hash on the same tick, so comparing hashes catches them drifting apart.
+pub fn state_hash(game_state: &GameState) -> u64 {
+    let mut hasher = Fnv1a::default();
+
+    hash_snake(&game_state.player, &mut hasher);
+    for rival in game_state.rivals.iter() {
+        hash_snake(&rival.snake, &mut hasher);
+        rival.score.hash(&mut hasher);
+        rival.alive.hash(&mut hasher);
+    }
+
+    for food in game_state.foods.iter() {
+        food.kind.hash(&mut hasher);
+        food.position.x.to_bits().hash(&mut hasher);
+        food.position.y.to_bits().hash(&mut hasher);
+        food.age.to_bits().hash(&mut hasher);
+        food.heading.to_bits().hash(&mut hasher);
+    }
+    for hazard in game_state.hazards.iter() {
+        hazard.position.x.to_bits().hash(&mut hasher);
+        hazard.position.y.to_bits().hash(&mut hasher);
+        hazard.age.to_bits().hash(&mut hasher);
+    }
+
+    game_state.food_spawn_timer.to_bits().hash(&mut hasher);
+    game_state.hazard_spawn_timer.to_bits().hash(&mut hasher);
+    game_state.run_time.to_bits().hash(&mut hasher);
+    game_state.score.hash(&mut hasher);
+    game_state.combo.hash(&mut hasher);
+    game_state.game_over.hash(&mut hasher);
+    game_state.round_wins.hash(&mut hasher);
+
+    // Where the random number generator has got to, without moving it on
+    game_state.rng.clone().next_u64().hash(&mut hasher);
+
+    hasher.finish()
+}
+
+fn hash_snake(snake: &Snake, hasher: &mut Fnv1a) {
+    snake.direction.hash(hasher);
+    for segment in snake.body.iter() {
+        segment.x.to_bits().hash(hasher);
+        segment.y.to_bits().hash(hasher);
+    }
+    snake.move_timer.to_bits().hash(hasher);
+    snake.move_interval.to_bits().hash(hasher);
+    snake.moves.hash(hasher);
+    for active in snake.active_effects.iter() {
+        active.effect.hash(hasher);
+        active.remaining.to_bits().hash(hasher);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        let mut hasher = Fnv1a::default();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn matches_the_published_fnv1a_test_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn numbers_hash_as_their_little_endian_bytes() {
+        let mut hasher = Fnv1a::default();
+        0x0102_0304u32.hash(&mut hasher);
+        assert_eq!(hasher.finish(), fnv1a(&[4, 3, 2, 1]));
+
+        let mut hasher = Fnv1a::default();
+        hasher.write_usize(7);
+        assert_eq!(hasher.finish(), fnv1a(&7u64.to_le_bytes()));
+    }
+}
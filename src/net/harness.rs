@@ -0,0 +1,260 @@
+use crate::ai::controller::{ArenaView, WormController};
+use crate::ai::{ControllerKind, Difficulty};
+use crate::audio::manager::AudioManager;
+use crate::graphics::sprites::SpriteMaps;
+use crate::net::checksum::state_hash;
+use crate::net::lockstep::NetError;
+use crate::net::rollback::{Rollback, RollbackStats};
+use crate::net::session::{NetMessage, PeerEvent, Transport};
+use crate::state::constants::gym::HEADLESS_DELTA_TIME;
+use crate::state::constants::net::HARNESS_SETTLE_FRAMES;
+use crate::state::constants::state::PLAYER_START;
+use crate::state::core::versus::start_next_round;
+use crate::state::structs::{Direction, GameState, Snake, WormId};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+/// How bad the simulated connection between the harness's two peers is, the same both ways
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConditions {
+    /// One-way delay every message gets
+    pub latency_ms: u64,
+    /// Most extra delay on top of `latency_ms`, picked at random per message, which also reorders messages
+    pub jitter_ms: u64,
+    /// Chance of a message never arriving, from 0 to 1
+    pub loss: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HarnessConfig {
+    pub conditions: LinkConditions,
+    /// Seeds the game as well as the connection's delays and losses
+    pub seed: u64,
+    /// Frames each peer plays, at one tick per frame
+    pub frames: u64,
+    pub input_delay: u64,
+}
+
+/// How a harness run went, for the host and the client in that order
+#[derive(Debug)]
+pub struct HarnessReport {
+    /// `Err` if the peers fell out of sync or lost each other
+    pub outcome: Result<(), NetError>,
+    pub ticks: [u64; 2],
+    pub confirmed_ticks: [u64; 2],
+    pub rounds_played: [u32; 2],
+    pub stats: [RollbackStats; 2],
+    /// The tick both peers were brought to after the run, with every input before it confirmed
+    pub final_tick: u64,
+    /// Each peer's `state_hash` at `final_tick`
+    pub final_hashes: [u64; 2],
+    /// The inputs each peer played its worm with, one per tick up to `final_tick`
+    pub inputs: [Vec<Option<Direction>>; 2],
+}
+
+/// Messages on their way in one direction, with the time each one arrives
+#[derive(Default)]
+struct Lane {
+    in_flight: Vec<(f64, NetMessage)>,
+    closed: bool,
+}
+
+/// One end of a connection simulated in memory, delivering messages late, out of order or not at all
+/// as its `LinkConditions` say. Time only moves on when the harness moves the shared clock.
+pub struct SimulatedLink {
+    outgoing: Rc<RefCell<Lane>>,
+    incoming: Rc<RefCell<Lane>>,
+    clock: Rc<Cell<f64>>,
+    conditions: LinkConditions,
+    rng: Rc<RefCell<StdRng>>,
+}
+
+impl SimulatedLink {
+    /// Both ends of a connection, and the clock in milliseconds that decides when messages arrive
+    pub fn pair(conditions: LinkConditions, seed: u64) -> (SimulatedLink, SimulatedLink, Rc<Cell<f64>>) {
+        let clock = Rc::new(Cell::new(0.0));
+        let rng = Rc::new(RefCell::new(StdRng::seed_from_u64(seed)));
+        let there = Rc::new(RefCell::new(Lane::default()));
+        let back = Rc::new(RefCell::new(Lane::default()));
+
+        let host = SimulatedLink { outgoing: there.clone(), incoming: back.clone(), clock: clock.clone(), conditions, rng: rng.clone() };
+        let client = SimulatedLink { outgoing: back, incoming: there, clock: clock.clone(), conditions, rng };
+        (host, client, clock)
+    }
+}
+
+impl Transport for SimulatedLink {
+    fn send(&mut self, message: &NetMessage) -> std::io::Result<()> {
+        let mut outgoing = self.outgoing.borrow_mut();
+        if outgoing.closed {
+            return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "The simulated link is closed"));
+        }
+
+        let mut rng = self.rng.borrow_mut();
+        if rng.random_bool(self.conditions.loss) {
+            return Ok(());
+        }
+        let jitter = rng.random_range(0..=self.conditions.jitter_ms);
+        let arrival = self.clock.get() + (self.conditions.latency_ms + jitter) as f64;
+        outgoing.in_flight.push((arrival, message.clone()));
+        Ok(())
+    }
+
+    fn try_recv(&mut self) -> Option<PeerEvent> {
+        let mut incoming = self.incoming.borrow_mut();
+        let now = self.clock.get();
+
+        let next = incoming
+            .in_flight
+            .iter()
+            .enumerate()
+            .filter(|(_, (arrival, _))| *arrival <= now)
+            .min_by(|(_, (a, _)), (_, (b, _))| a.total_cmp(b))
+            .map(|(index, _)| index);
+
+        match next {
+            Some(index) => Some(PeerEvent::Message(incoming.in_flight.remove(index).1)),
+            None if incoming.closed && incoming.in_flight.is_empty() => Some(PeerEvent::Closed),
+            None => None,
+        }
+    }
+}
+
+impl Drop for SimulatedLink {
+    /// Hanging up closes the connection both ways, like a socket would
+    fn drop(&mut self) {
+        self.outgoing.borrow_mut().closed = true;
+        self.incoming.borrow_mut().closed = true;
+    }
+}
+
+/// One side of the harness: its own copy of the game, steered by a computer-controlled player
+struct HarnessPeer {
+    game_state: GameState<'static>,
+    session: Rollback<SimulatedLink>,
+    worm: WormId,
+    controller: Box<dyn WormController>,
+    decided_at_move: Option<u32>,
+    rounds_played: u32,
+    input_delay: u64,
+    /// Every input this side has scheduled, by the tick it's for
+    inputs: BTreeMap<u64, Option<Direction>>,
+}
+
+impl HarnessPeer {
+    fn new(link: SimulatedLink, worm: WormId, config: &HarnessConfig) -> Self {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        let mut game_state = GameState::new(player, SpriteMaps::empty(), 0, 0, None, AudioManager::silent(), Vec::new(), config.seed);
+        game_state.start_versus(u32::MAX);
+
+        HarnessPeer {
+            game_state,
+            session: Rollback::new(link, worm, config.input_delay),
+            worm,
+            controller: ControllerKind::FloodFill.create(Difficulty::NORMAL),
+            decided_at_move: None,
+            rounds_played: 0,
+            input_delay: config.input_delay,
+            inputs: (0..config.input_delay).map(|tick| (tick, None)).collect(),
+        }
+    }
+
+    /// What a player watching this side's screen would press: a new direction once per step of their worm,
+    /// decided on the game as this side currently believes it to be
+    fn choose_input(&mut self) -> Option<Direction> {
+        let snake = self.game_state.snake(self.worm);
+        if self.decided_at_move == Some(snake.moves) {
+            return None;
+        }
+        self.decided_at_move = Some(snake.moves);
+
+        let current = snake.direction;
+        let direction = self.controller.choose_direction(&ArenaView::new(&self.game_state, self.worm));
+        (direction != current && direction != current.opposite()).then_some(direction)
+    }
+
+    /// Sends `direction` and hears from the other side, then moves the game on unless told to hold still
+    fn play_frame(&mut self, direction: Option<Direction>, may_advance: bool) -> Result<(), NetError> {
+        let tick = self.session.tick() + self.input_delay;
+        if self.session.send_input(direction)? {
+            self.inputs.insert(tick, direction);
+        }
+        self.session.poll(&mut self.game_state)?;
+        if may_advance {
+            self.session.advance(&mut self.game_state)?;
+        }
+
+        if let Some(outcome) = self.session.take_round_over() {
+            start_next_round(&mut self.game_state, outcome);
+            self.rounds_played += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Plays an online versus match between two in-process peers over a simulated connection, each side with
+/// its own copy of the game and a computer-controlled player. Everything, including the connection's
+/// delays and losses, follows from `config.seed`, so a troublesome run can be played again exactly.
+///
+/// Once the frames are played, both peers stop pressing keys and the one behind catches up, until both are at
+/// the same tick with nothing left to guess. Their games have to be the same there for the run to count as in sync.
+pub fn run_rollback_harness(config: &HarnessConfig) -> HarnessReport {
+    let (host_link, client_link, clock) = SimulatedLink::pair(config.conditions, config.seed);
+    let mut peers = [HarnessPeer::new(host_link, WormId::Player, config), HarnessPeer::new(client_link, WormId::Rival(0), config)];
+
+    let frame_ms = HEADLESS_DELTA_TIME as f64 * 1000.0;
+    let mut outcome = play_frames(&mut peers, &clock, frame_ms, config.frames);
+    if outcome.is_ok() {
+        outcome = settle(&mut peers, &clock, frame_ms);
+    }
+
+    let final_tick = peers[0].session.tick().min(peers[1].session.tick());
+    let final_hashes = [state_hash(&peers[0].game_state), state_hash(&peers[1].game_state)];
+    if outcome.is_ok() && final_hashes[0] != final_hashes[1] {
+        outcome = Err(NetError::Desync { tick: final_tick });
+    }
+
+    HarnessReport {
+        outcome,
+        ticks: [peers[0].session.tick(), peers[1].session.tick()],
+        confirmed_ticks: [peers[0].session.confirmed_tick(), peers[1].session.confirmed_tick()],
+        rounds_played: [peers[0].rounds_played, peers[1].rounds_played],
+        stats: [peers[0].session.stats(), peers[1].session.stats()],
+        final_tick,
+        final_hashes,
+        inputs: peers.each_ref().map(|peer| peer.inputs.range(..final_tick).map(|(_, &direction)| direction).collect()),
+    }
+}
+
+fn play_frames(peers: &mut [HarnessPeer; 2], clock: &Cell<f64>, frame_ms: f64, frames: u64) -> Result<(), NetError> {
+    for _ in 0..frames {
+        clock.set(clock.get() + frame_ms);
+        for peer in peers.iter_mut() {
+            let direction = peer.choose_input();
+            peer.play_frame(direction, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Brings both peers to the same tick with every input before it confirmed, giving up as having lost each
+/// other after `HARNESS_SETTLE_FRAMES`
+fn settle(peers: &mut [HarnessPeer; 2], clock: &Cell<f64>, frame_ms: f64) -> Result<(), NetError> {
+    for _ in 0..HARNESS_SETTLE_FRAMES {
+        clock.set(clock.get() + frame_ms);
+        let target_tick = peers[0].session.tick().max(peers[1].session.tick());
+        for peer in peers.iter_mut() {
+            let behind = peer.session.tick() < target_tick;
+            peer.play_frame(None, behind)?;
+        }
+
+        let settled = |peer: &HarnessPeer| peer.session.tick() == target_tick && peer.session.confirmed_tick() >= target_tick;
+        if peers.iter().all(settled) {
+            return Ok(());
+        }
+    }
+    Err(NetError::Disconnected)
+}
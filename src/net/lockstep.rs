@@ -0,0 +1,182 @@
+use crate::net::checksum::state_hash;
+use crate::net::session::{NetMessage, Peer, PeerEvent};
+use crate::state::constants::gym::HEADLESS_DELTA_TIME;
+use crate::state::constants::net::{HASH_INTERVAL, NET_TIMEOUT_SECONDS};
+use crate::state::core::{execute_core_logic, initialize_online_core_logic_map, CoreLogic};
+use crate::state::structs::{Direction, GameState, WormId};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Why an online match can't go on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetError {
+    /// The other player left, hung up or went quiet for too long
+    Disconnected,
+    /// The two simulations no longer agree, first noticed at `tick`
+    Desync { tick: u64 },
+    /// A wrong guess turned up for a tick there's no snapshot of to go back to, a bug in the rollback bookkeeping
+    MissingSnapshot { tick: u64 },
+}
+
+/// Runs one game on two machines by having both simulate it from the same seed, trading nothing but each player's
+/// input for every tick. A tick only runs once both inputs for it are in, and inputs are sent `input_delay` ticks
+/// ahead so that they usually are.
+pub struct Lockstep {
+    peer: Peer,
+    local_worm: WormId,
+    remote_worm: WormId,
+    input_delay: u64,
+    /// The next tick to simulate
+    tick: u64,
+    local_inputs: BTreeMap<u64, Option<Direction>>,
+    remote_inputs: BTreeMap<u64, Option<Direction>>,
+    local_hashes: BTreeMap<u64, u64>,
+    remote_hashes: BTreeMap<u64, u64>,
+    /// When the current tick started waiting on the other player's input
+    stalled_since: Option<Instant>,
+    core_logic: Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)>,
+}
+
+impl Lockstep {
+    /// `local_worm` is the one played on this machine, the other player's being the other one in the versus arena
+    pub fn new(peer: Peer, local_worm: WormId, input_delay: u64) -> Self {
+        let remote_worm = if local_worm == WormId::Player { WormId::Rival(0) } else { WormId::Player };
+
+        // Nobody has had the chance to turn during the first few ticks
+        let mut local_inputs = BTreeMap::new();
+        let mut remote_inputs = BTreeMap::new();
+        for tick in 0..input_delay {
+            local_inputs.insert(tick, None);
+            remote_inputs.insert(tick, None);
+        }
+
+        Lockstep {
+            peer,
+            local_worm,
+            remote_worm,
+            input_delay,
+            tick: 0,
+            local_inputs,
+            remote_inputs,
+            local_hashes: BTreeMap::new(),
+            remote_hashes: BTreeMap::new(),
+            stalled_since: None,
+            core_logic: initialize_online_core_logic_map(),
+        }
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn local_worm(&self) -> WormId {
+        self.local_worm
+    }
+
+    /// How long the current tick has been waiting on the other player
+    pub fn stalled_for(&self) -> Option<Duration> {
+        self.stalled_since.map(|since| since.elapsed())
+    }
+
+    /// Schedules the local player's input `input_delay` ticks ahead, unless that tick already has one.
+    /// Returns whether `direction` was used, so a key press can be held over to the next tick otherwise.
+    pub fn send_input(&mut self, direction: Option<Direction>) -> Result<bool, NetError> {
+        let tick = self.tick + self.input_delay;
+        if self.local_inputs.contains_key(&tick) {
+            return Ok(false);
+        }
+
+        self.local_inputs.insert(tick, direction);
+        self.send(&NetMessage::Input { tick, direction })?;
+        Ok(true)
+    }
+
+    /// Takes in everything the other player has sent since the last call
+    pub fn poll(&mut self) -> Result<(), NetError> {
+        while let Some(event) = self.peer.try_recv() {
+            match event {
+                PeerEvent::Message(NetMessage::Input { tick, direction }) => {
+                    self.remote_inputs.insert(tick, direction);
+                }
+                PeerEvent::Message(NetMessage::Hash { tick, hash }) => {
+                    self.remote_hashes.insert(tick, hash);
+                }
+                PeerEvent::Message(NetMessage::Hello { .. } | NetMessage::Inputs { .. }) => {}
+                PeerEvent::Message(NetMessage::Bye) | PeerEvent::Closed => return Err(NetError::Disconnected),
+            }
+        }
+
+        self.compare_hashes()?;
+
+        if self.stalled_for().is_some_and(|stalled| stalled >= Duration::from_secs(NET_TIMEOUT_SECONDS)) {
+            return Err(NetError::Disconnected);
+        }
+        Ok(())
+    }
+
+    /// Simulates the next tick if both players' inputs for it are in, returning whether it did. Rounds are
+    /// left for the caller to settle after each tick, the same way on both sides.
+    pub fn advance(&mut self, game_state: &mut GameState) -> Result<bool, NetError> {
+        let (Some(&local), Some(&remote)) = (self.local_inputs.get(&self.tick), self.remote_inputs.get(&self.tick)) else {
+            self.stalled_since.get_or_insert_with(Instant::now);
+            return Ok(false);
+        };
+        self.stalled_since = None;
+        self.local_inputs.remove(&self.tick);
+        self.remote_inputs.remove(&self.tick);
+
+        simulate_tick(game_state, &self.core_logic, [(self.local_worm, local), (self.remote_worm, remote)]);
+
+        if self.tick.is_multiple_of(HASH_INTERVAL) {
+            let hash = state_hash(game_state);
+            self.local_hashes.insert(self.tick, hash);
+            self.send(&NetMessage::Hash { tick: self.tick, hash })?;
+            self.compare_hashes()?;
+        }
+
+        self.tick += 1;
+        Ok(true)
+    }
+
+    /// Tells the other player this side is leaving, as far as it still can
+    pub fn leave(mut self) {
+        let _ = self.peer.send(&NetMessage::Bye);
+    }
+
+    fn send(&mut self, message: &NetMessage) -> Result<(), NetError> {
+        self.peer.send(message).map_err(|_| NetError::Disconnected)
+    }
+
+    fn compare_hashes(&mut self) -> Result<(), NetError> {
+        let both: Vec<u64> = self.local_hashes.keys().filter(|tick| self.remote_hashes.contains_key(tick)).copied().collect();
+        for tick in both {
+            let local = self.local_hashes.remove(&tick);
+            let remote = self.remote_hashes.remove(&tick);
+            if local != remote {
+                return Err(NetError::Desync { tick });
+            }
+        }
+        Ok(())
+    }
+}
+
+
+/// Turns each worm as its player asked for this tick and runs the tick, at the fixed rate both sides share
+pub fn simulate_tick(
+    game_state: &mut GameState,
+    core_logic: &[(&'static str, Rc<RefCell<dyn CoreLogic>>)],
+    mut inputs: [(WormId, Option<Direction>); 2],
+) {
+    // Host's worm first, so both sides turn the worms in the same order
+    inputs.sort_by_key(|&(worm, _)| worm != WormId::Player);
+    for (worm, direction) in inputs {
+        if let Some(direction) = direction {
+            game_state.snake_mut(worm).direction = direction;
+        }
+    }
+
+    game_state.fixed_delta_time = Some(HEADLESS_DELTA_TIME);
+    execute_core_logic(game_state, core_logic);
+}
\ No newline at end of file
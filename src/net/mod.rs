@@ -0,0 +1,6 @@
+pub mod checksum;
+pub mod harness;
+pub mod lockstep;
+pub mod online;
+pub mod rollback;
+pub mod session;
@@ -0,0 +1,267 @@
+use crate::ai::controller::{ArenaView, WormController};
+use crate::audio::director::Scene;
+use crate::graphics::render::render_pixel_buffer;
+use crate::graphics::update::{draw_match_over_screen, draw_net_status_screen, update_pixel_buffer};
+use crate::net::lockstep::{Lockstep, NetError};
+use crate::net::rollback::Rollback;
+use crate::net::session::{accept_peer, connect, greet_client, greet_host, host, MatchSettings, Peer};
+use crate::state::constants::net::{HEADLESS_ACCEPT_SECONDS, MAX_PREDICTION_TICKS, STALL_NOTICE_MILLIS};
+use crate::state::constants::state::{FRAME_RATE_SLEEP_DURATION, ROUND_OVER_SECONDS};
+use crate::state::constants::text::{
+    NET_CANCEL_HINT, NET_CONNECTING, NET_DESYNC, NET_DISCONNECTED, NET_MISSING_SNAPSHOT, NET_QUIT_HINT, NET_REMATCH_HINT, NET_STALLED, NET_WAITING_FOR_PEER,
+};
+use crate::state::core::versus::{settle_round, show_round_over, start_next_round, RoundOutcome};
+use crate::state::structs::{Direction, GameState, WormId};
+use minifb::{Key, KeyRepeat};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::net::TcpListener;
+use std::{thread, time};
+
+/// Which end of the connection this instance is
+#[derive(Debug, Clone)]
+pub enum NetRole {
+    /// Waits on `port` for the other player, who plays by `settings`
+    Host { port: u16, settings: MatchSettings },
+    /// Connects to a host at `address`, e.g. `192.168.1.20:7777`
+    Join { address: String },
+}
+
+/// Plays a versus match against someone on another machine, the host as player one and the one joining as
+/// player two. Both steer with either WASD or the arrow keys.
+pub fn start_online_versus(mut game_state: GameState, role: NetRole) -> Result<(), Box<dyn std::error::Error>> {
+    let (peer, settings, local_worm) = match role {
+        NetRole::Host { port, settings } => {
+            let waiting_text = format!("{}{}", NET_WAITING_FOR_PEER, port);
+            let peer = host(port, || {
+                draw_net_status_screen(&mut game_state, &waiting_text, NET_CANCEL_HINT);
+                render_pixel_buffer(&mut game_state);
+                game_state.is_window_open() && !game_state.is_key_down(Key::Escape)
+            })?;
+            let Some(mut peer) = peer else {
+                return Ok(());
+            };
+            greet_client(&mut peer, settings)?;
+            (peer, settings, WormId::Player)
+        }
+        NetRole::Join { address } => {
+            draw_net_status_screen(&mut game_state, &format!("{}{}", NET_CONNECTING, address), "");
+            render_pixel_buffer(&mut game_state);
+
+            let mut peer = connect(&address)?;
+            let settings = greet_host(&mut peer)?;
+            (peer, settings, WormId::Rival(0))
+        }
+    };
+
+    let mut netcode = start_match(&mut game_state, peer, settings, local_worm);
+    let mut pending_direction = None;
+
+    while game_state.is_window_open() {
+        if game_state.is_key_down(Key::Escape) {
+            netcode.leave();
+            return Ok(());
+        }
+
+        game_state.audio_manager.update();
+
+        if let Some(direction) = local_direction(&game_state) {
+            pending_direction = Some(direction);
+        }
+
+        match netcode.play_tick(&mut game_state, &mut pending_direction) {
+            Ok(Some(outcome)) => {
+                show_round_over(&mut game_state, outcome.winner);
+                if let Some(match_winner) = outcome.match_winner {
+                    show_match_over(&mut game_state, match_winner);
+                }
+                start_next_round(&mut game_state, outcome);
+                update_pixel_buffer(&mut game_state);
+            }
+            Ok(None) => {
+                if netcode.stalled_for().is_some_and(|stalled| stalled >= time::Duration::from_millis(STALL_NOTICE_MILLIS)) {
+                    draw_net_status_screen(&mut game_state, NET_STALLED, NET_QUIT_HINT);
+                } else {
+                    update_pixel_buffer(&mut game_state);
+                }
+            }
+            Err(error) => {
+                netcode.leave();
+                show_net_error(&mut game_state, error);
+                return Ok(());
+            }
+        }
+
+        render_pixel_buffer(&mut game_state);
+        thread::sleep(time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+
+    netcode.leave();
+    Ok(())
+}
+
+/// Plays an online match with no window and no keyboard, the local worm steered by `controller`, until both sides
+/// are `frames` ticks in. A host prints the port it listens on once it does, so a script can start the other side
+/// and tell it where to connect. Returns the tick this side stopped at.
+pub fn play_headless_online_versus(
+    mut game_state: GameState,
+    role: NetRole,
+    mut controller: Box<dyn WormController>,
+    frames: u64,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let (peer, settings, local_worm) = match role {
+        NetRole::Host { port, settings } => {
+            let listener = TcpListener::bind(("0.0.0.0", port))?;
+            println!("{}{}", NET_WAITING_FOR_PEER, listener.local_addr()?.port());
+
+            let waiting_since = time::Instant::now();
+            let Some(mut peer) = accept_peer(&listener, || waiting_since.elapsed().as_secs() < HEADLESS_ACCEPT_SECONDS)? else {
+                return Err("Nobody joined".into());
+            };
+            greet_client(&mut peer, settings)?;
+            (peer, settings, WormId::Player)
+        }
+        NetRole::Join { address } => {
+            let mut peer = connect(&address)?;
+            let settings = greet_host(&mut peer)?;
+            (peer, settings, WormId::Rival(0))
+        }
+    };
+
+    let mut netcode = start_match(&mut game_state, peer, settings, local_worm);
+
+    // Neither side gets further ahead of the other than this, so whoever gets here first can leave knowing the
+    // other is past `frames` too
+    let last_tick = frames + settings.input_delay + MAX_PREDICTION_TICKS + 1;
+    while netcode.tick() < last_tick {
+        let mut pending_direction = Some(controller.choose_direction(&ArenaView::new(&game_state, local_worm)));
+
+        match netcode.play_tick(&mut game_state, &mut pending_direction) {
+            Ok(Some(outcome)) => start_next_round(&mut game_state, outcome),
+            Ok(None) => {}
+            Err(NetError::Disconnected) if netcode.tick() >= frames => return Ok(netcode.tick()),
+            Err(error) => return Err(net_error_text(error).into()),
+        }
+
+        thread::sleep(time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+
+    let tick = netcode.tick();
+    netcode.leave();
+    Ok(tick)
+}
+
+/// Same seed, same rules, same inputs: the same game on both machines
+fn start_match(game_state: &mut GameState, peer: Peer, settings: MatchSettings, local_worm: WormId) -> Netcode {
+    game_state.rng = StdRng::seed_from_u64(settings.seed);
+    game_state.start_versus(settings.rounds_to_win);
+    game_state.audio_manager.set_scene(Scene::Gameplay);
+
+    if settings.rollback {
+        Netcode::Rollback(Box::new(Rollback::new(peer, local_worm, settings.input_delay)))
+    } else {
+        Netcode::Lockstep(Lockstep::new(peer, local_worm, settings.input_delay))
+    }
+}
+
+/// How the two sides keep their games together, as the host chose
+enum Netcode {
+    Lockstep(Lockstep),
+    Rollback(Box<Rollback<Peer>>),
+}
+
+impl Netcode {
+    /// Sends this tick's input, hears from the other player and moves the game on if it can. Returns the
+    /// outcome of a round that has just ended, for the caller to show before starting the next one.
+    fn play_tick(&mut self, game_state: &mut GameState, pending_direction: &mut Option<Direction>) -> Result<Option<RoundOutcome>, NetError> {
+        match self {
+            Netcode::Lockstep(lockstep) => {
+                if lockstep.send_input(*pending_direction)? {
+                    *pending_direction = None;
+                }
+                lockstep.poll()?;
+                let advanced = lockstep.advance(game_state)?;
+                Ok(if advanced { settle_round(game_state) } else { None })
+            }
+            Netcode::Rollback(rollback) => {
+                if rollback.send_input(*pending_direction)? {
+                    *pending_direction = None;
+                }
+                rollback.poll(game_state)?;
+                rollback.advance(game_state)?;
+                Ok(rollback.take_round_over())
+            }
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        match self {
+            Netcode::Lockstep(lockstep) => lockstep.tick(),
+            Netcode::Rollback(rollback) => rollback.tick(),
+        }
+    }
+
+    fn stalled_for(&self) -> Option<time::Duration> {
+        match self {
+            Netcode::Lockstep(lockstep) => lockstep.stalled_for(),
+            Netcode::Rollback(rollback) => rollback.stalled_for(),
+        }
+    }
+
+    fn leave(self) {
+        match self {
+            Netcode::Lockstep(lockstep) => lockstep.leave(),
+            Netcode::Rollback(rollback) => rollback.leave(),
+        }
+    }
+}
+
+fn local_direction(game_state: &GameState) -> Option<Direction> {
+    let key_direction_map = [
+        (Key::W, Direction::Up),
+        (Key::A, Direction::Left),
+        (Key::S, Direction::Down),
+        (Key::D, Direction::Right),
+        (Key::Up, Direction::Up),
+        (Key::Left, Direction::Left),
+        (Key::Down, Direction::Down),
+        (Key::Right, Direction::Right),
+    ];
+
+    key_direction_map.iter().find(|(key, _)| game_state.is_key_pressed(*key, KeyRepeat::Yes)).map(|&(_, direction)| direction)
+}
+
+/// Unlike a local match, nobody has to press anything for the rematch, which both sides start on their own
+fn show_match_over(game_state: &mut GameState, winner: usize) {
+    let frames = ROUND_OVER_SECONDS * 1000 / FRAME_RATE_SLEEP_DURATION;
+    for _ in 0..frames {
+        if !game_state.is_window_open() {
+            break;
+        }
+
+        draw_match_over_screen(game_state, winner, NET_REMATCH_HINT);
+        render_pixel_buffer(game_state);
+        game_state.audio_manager.update();
+        thread::sleep(time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+}
+
+/// Keeps the reason the match ended up until the window is closed or Escape is pressed
+fn show_net_error(game_state: &mut GameState, error: NetError) {
+    let text = net_error_text(error);
+
+    while game_state.is_window_open() && !game_state.is_key_down(Key::Escape) {
+        draw_net_status_screen(game_state, &text, NET_QUIT_HINT);
+        render_pixel_buffer(game_state);
+        game_state.audio_manager.update();
+        thread::sleep(time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+}
+
+fn net_error_text(error: NetError) -> String {
+    match error {
+        NetError::Disconnected => NET_DISCONNECTED.to_string(),
+        NetError::Desync { tick } => format!("{}{}", NET_DESYNC, tick),
+        NetError::MissingSnapshot { tick } => format!("{}{}", NET_MISSING_SNAPSHOT, tick),
+    }
+}
@@ -0,0 +1,298 @@
+use crate::net::checksum::state_hash;
+use crate::net::lockstep::{simulate_tick, NetError};
+use crate::net::session::{NetMessage, PeerEvent, Transport};
+use crate::state::constants::net::{HASH_INTERVAL, HASH_MEMORY_TICKS, MAX_PREDICTION_TICKS, NET_TIMEOUT_SECONDS};
+use crate::state::core::versus::{settle_round, RoundOutcome};
+use crate::state::core::{initialize_online_core_logic_map, CoreLogic};
+use crate::state::structs::{Direction, GameState, Snapshot, WormId};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// How much correcting a rollback session has had to do so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RollbackStats {
+    /// Times a wrong guess at the other player's input sent the game back
+    pub rollbacks: u64,
+    /// Ticks played again after going back
+    pub resimulated_ticks: u64,
+    pub longest_rollback: u64,
+    /// Frames spent waiting because the game had got too far ahead of the other player
+    pub stalled_frames: u64,
+    /// State hashes found to agree with the other player's
+    pub hashes_checked: u64,
+}
+
+/// Runs one game on two machines without waiting on the network, GGPO style. The other player's input for a tick
+/// that hasn't arrived yet is guessed to be nothing, i.e. their worm keeps going. When it does arrive and the guess
+/// was wrong, the game goes back to a snapshot from before that tick and plays up to the present again with the
+/// real input. Neither side gets more than `MAX_PREDICTION_TICKS` ahead of what it knows about the other.
+pub struct Rollback<T: Transport> {
+    transport: T,
+    local_worm: WormId,
+    remote_worm: WormId,
+    input_delay: u64,
+    /// The next tick to simulate
+    tick: u64,
+    /// Every remote input before this tick is known
+    confirmed_tick: u64,
+    /// The first tick the other player still needs this side's input for
+    remote_ack: u64,
+    local_inputs: BTreeMap<u64, Option<Direction>>,
+    remote_inputs: BTreeMap<u64, Option<Direction>>,
+    /// Guesses already simulated with, to check against the real inputs once they arrive
+    predictions: BTreeMap<u64, Option<Direction>>,
+    /// The game as it was before each tick that might still have to be played again
+    snapshots: BTreeMap<u64, Snapshot>,
+    /// Hashes of ticks that were played on guesses, sent once the guesses turn out right
+    speculative_hashes: BTreeMap<u64, u64>,
+    local_hashes: BTreeMap<u64, u64>,
+    remote_hashes: BTreeMap<u64, u64>,
+    /// A round that ended on the tick given, held until it's certain the other side saw it end too
+    round_over: Option<(u64, RoundOutcome)>,
+    /// When the game last had to stop and wait for the other player
+    stalled_since: Option<Instant>,
+    stats: RollbackStats,
+    core_logic: Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)>,
+}
+
+impl<T: Transport> Rollback<T> {
+    /// `local_worm` is the one played on this machine, the other player's being the other one in the versus arena
+    pub fn new(transport: T, local_worm: WormId, input_delay: u64) -> Self {
+        let remote_worm = if local_worm == WormId::Player { WormId::Rival(0) } else { WormId::Player };
+
+        // Nobody has had the chance to turn during the first few ticks
+        let mut local_inputs = BTreeMap::new();
+        let mut remote_inputs = BTreeMap::new();
+        for tick in 0..input_delay {
+            local_inputs.insert(tick, None);
+            remote_inputs.insert(tick, None);
+        }
+
+        Rollback {
+            transport,
+            local_worm,
+            remote_worm,
+            input_delay,
+            tick: 0,
+            confirmed_tick: input_delay,
+            remote_ack: 0,
+            local_inputs,
+            remote_inputs,
+            predictions: BTreeMap::new(),
+            snapshots: BTreeMap::new(),
+            speculative_hashes: BTreeMap::new(),
+            local_hashes: BTreeMap::new(),
+            remote_hashes: BTreeMap::new(),
+            round_over: None,
+            stalled_since: None,
+            stats: RollbackStats::default(),
+            core_logic: initialize_online_core_logic_map(),
+        }
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Every tick before this one was played with both players' real inputs
+    pub fn confirmed_tick(&self) -> u64 {
+        self.confirmed_tick
+    }
+
+    pub fn stats(&self) -> RollbackStats {
+        self.stats
+    }
+
+    /// How long the game has been waiting on the other player
+    pub fn stalled_for(&self) -> Option<Duration> {
+        self.stalled_since.map(|since| since.elapsed())
+    }
+
+    /// Schedules the local player's input `input_delay` ticks ahead, unless that tick already has one, and sends
+    /// every input the other player hasn't acknowledged yet. Returns whether `direction` was used, so a key press
+    /// can be held over to the next tick otherwise.
+    pub fn send_input(&mut self, direction: Option<Direction>) -> Result<bool, NetError> {
+        let tick = self.tick + self.input_delay;
+        let used = !self.local_inputs.contains_key(&tick);
+        if used {
+            self.local_inputs.insert(tick, direction);
+        }
+
+        let start_tick = self.remote_ack;
+        let directions = self.local_inputs.range(start_tick..).map(|(_, &direction)| direction).collect();
+        self.send(&NetMessage::Inputs { start_tick, directions, ack: self.confirmed_tick })?;
+        Ok(used)
+    }
+
+    /// Takes in everything the other player has sent since the last call, going back and playing the game
+    /// again from the first tick a guess at their input was wrong
+    pub fn poll(&mut self, game_state: &mut GameState) -> Result<(), NetError> {
+        while let Some(event) = self.transport.try_recv() {
+            match event {
+                PeerEvent::Message(NetMessage::Inputs { start_tick, directions, ack }) => {
+                    self.remote_ack = self.remote_ack.max(ack);
+                    for (tick, direction) in (start_tick..).zip(directions) {
+                        if tick >= self.confirmed_tick {
+                            self.remote_inputs.entry(tick).or_insert(direction);
+                        }
+                    }
+                }
+                PeerEvent::Message(NetMessage::Hash { tick, hash }) => {
+                    self.remote_hashes.insert(tick, hash);
+                }
+                PeerEvent::Message(NetMessage::Hello { .. } | NetMessage::Input { .. }) => {}
+                PeerEvent::Message(NetMessage::Bye) | PeerEvent::Closed => return Err(NetError::Disconnected),
+            }
+        }
+
+        let mut mispredicted = None;
+        while let Some(&direction) = self.remote_inputs.get(&self.confirmed_tick) {
+            if self.predictions.remove(&self.confirmed_tick).is_some_and(|guess| guess != direction) {
+                mispredicted.get_or_insert(self.confirmed_tick);
+            }
+            self.confirmed_tick += 1;
+        }
+
+        if let Some(from) = mispredicted {
+            self.roll_back(game_state, from)?;
+        }
+
+        self.forget_confirmed();
+        self.share_confirmed_hashes()?;
+        self.compare_hashes()?;
+
+        if self.stalled_for().is_some_and(|stalled| stalled >= Duration::from_secs(NET_TIMEOUT_SECONDS)) {
+            return Err(NetError::Disconnected);
+        }
+        Ok(())
+    }
+
+    /// Simulates the next tick, guessing at the other player's input if it isn't in yet, unless the game is
+    /// already as far ahead of them as it may get. Returns whether it did.
+    pub fn advance(&mut self, game_state: &mut GameState) -> Result<bool, NetError> {
+        let too_far_ahead = self.tick >= self.confirmed_tick + MAX_PREDICTION_TICKS;
+        if self.round_over.is_some() || too_far_ahead || !self.local_inputs.contains_key(&self.tick) {
+            self.stalled_since.get_or_insert_with(Instant::now);
+            self.stats.stalled_frames += 1;
+            return Ok(false);
+        }
+        self.stalled_since = None;
+
+        self.play_tick(game_state);
+        Ok(true)
+    }
+
+    /// The outcome of a round once both sides are certain to have seen it end the same way. The game holds still
+    /// until then, and the caller has to start the next round with `start_next_round` before advancing again.
+    pub fn take_round_over(&mut self) -> Option<RoundOutcome> {
+        match self.round_over {
+            Some((tick, outcome)) if tick < self.confirmed_tick => {
+                self.round_over = None;
+                Some(outcome)
+            }
+            _ => None,
+        }
+    }
+
+    /// Tells the other player this side is leaving, as far as it still can
+    pub fn leave(mut self) {
+        let _ = self.transport.send(&NetMessage::Bye);
+    }
+
+    fn play_tick(&mut self, game_state: &mut GameState) {
+        let tick = self.tick;
+        self.snapshots.insert(tick, game_state.snapshot());
+
+        let local = self.local_inputs.get(&tick).copied().flatten();
+        let remote = match self.remote_inputs.get(&tick) {
+            Some(&direction) => direction,
+            None => {
+                self.predictions.insert(tick, None);
+                None
+            }
+        };
+        simulate_tick(game_state, &self.core_logic, [(self.local_worm, local), (self.remote_worm, remote)]);
+
+        if tick.is_multiple_of(HASH_INTERVAL) {
+            self.speculative_hashes.insert(tick, state_hash(game_state));
+        }
+        if let Some(outcome) = settle_round(game_state) {
+            self.round_over = Some((tick, outcome));
+        }
+
+        self.tick += 1;
+    }
+
+    /// Goes back to before tick `from` and plays up to where the game was, or to the end of a round on the way
+    fn roll_back(&mut self, game_state: &mut GameState, from: u64) -> Result<(), NetError> {
+        let Some(snapshot) = self.snapshots.get(&from) else {
+            return Err(NetError::MissingSnapshot { tick: from });
+        };
+        game_state.restore(snapshot);
+
+        let present = self.tick;
+        self.tick = from;
+        self.round_over = self.round_over.filter(|&(tick, _)| tick < from);
+        self.predictions.retain(|&tick, _| tick < from);
+        self.speculative_hashes.retain(|&tick, _| tick < from);
+        self.snapshots.retain(|&tick, _| tick < from);
+
+        // Effects were already heard the first time round
+        game_state.audio_manager.set_sfx_muted(true);
+        while self.tick < present && self.round_over.is_none() {
+            self.play_tick(game_state);
+        }
+        game_state.audio_manager.set_sfx_muted(false);
+
+        let depth = present - from;
+        self.stats.rollbacks += 1;
+        self.stats.resimulated_ticks += self.tick - from;
+        self.stats.longest_rollback = self.stats.longest_rollback.max(depth);
+        Ok(())
+    }
+
+    /// Drops what can no longer be needed: nothing before the confirmed tick will be played again
+    fn forget_confirmed(&mut self) {
+        let confirmed_tick = self.confirmed_tick.min(self.tick);
+        self.snapshots.retain(|&tick, _| tick >= confirmed_tick);
+        self.remote_inputs.retain(|&tick, _| tick >= confirmed_tick);
+
+        let oldest_needed = confirmed_tick.min(self.remote_ack);
+        self.local_inputs.retain(|&tick, _| tick >= oldest_needed);
+
+        let oldest_hash = confirmed_tick.saturating_sub(HASH_MEMORY_TICKS);
+        self.local_hashes.retain(|&tick, _| tick >= oldest_hash);
+        self.remote_hashes.retain(|&tick, _| tick >= oldest_hash);
+    }
+
+    /// Sends the hashes of ticks whose guesses have all turned out right, which won't change any more
+    fn share_confirmed_hashes(&mut self) -> Result<(), NetError> {
+        let confirmed: Vec<(u64, u64)> =
+            self.speculative_hashes.range(..self.confirmed_tick).map(|(&tick, &hash)| (tick, hash)).collect();
+        for (tick, hash) in confirmed {
+            self.speculative_hashes.remove(&tick);
+            self.local_hashes.insert(tick, hash);
+            self.send(&NetMessage::Hash { tick, hash })?;
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, message: &NetMessage) -> Result<(), NetError> {
+        self.transport.send(message).map_err(|_| NetError::Disconnected)
+    }
+
+    fn compare_hashes(&mut self) -> Result<(), NetError> {
+        let both: Vec<u64> = self.local_hashes.keys().filter(|tick| self.remote_hashes.contains_key(tick)).copied().collect();
+        for tick in both {
+            let local = self.local_hashes.remove(&tick);
+            let remote = self.remote_hashes.remove(&tick);
+            if local != remote {
+                return Err(NetError::Desync { tick });
+            }
+            self.stats.hashes_checked += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use crate::state::constants::net::{CONNECT_TIMEOUT_SECONDS, NET_PROTOCOL_VERSION, NET_TIMEOUT_SECONDS};
+use crate::state::constants::state::FRAME_RATE_SLEEP_DURATION;
+use crate::state::structs::Direction;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::Duration;
+
+/// What the host decides for both sides of an online match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchSettings {
+    pub seed: u64,
+    pub rounds_to_win: u32,
+    /// Ticks between a player pressing a key and their worm turning
+    pub input_delay: u64,
+    /// Guess the other player's inputs and correct course when a guess was wrong, instead of waiting for them
+    pub rollback: bool,
+}
+
+/// Everything the two peers ever tell each other, one JSON object per line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetMessage {
+    /// Sent by both sides on connecting, only the host's settings count
+    Hello { version: u32, settings: MatchSettings },
+    /// The sender's turn for `tick`, `None` to keep going straight
+    Input { tick: u64, direction: Option<Direction> },
+    /// The sender's inputs from `start_tick` on, resent until acknowledged so a lost message costs nothing.
+    /// `ack` is the first tick the sender is still missing the receiver's input for.
+    Inputs { start_tick: u64, directions: Vec<Option<Direction>>, ack: u64 },
+    /// The sender's `state_hash` after simulating `tick`
+    Hash { tick: u64, hash: u64 },
+    /// The sender is leaving the match
+    Bye,
+}
+
+pub enum PeerEvent {
+    Message(NetMessage),
+    /// The connection is gone, or the other side sent something that isn't a message
+    Closed,
+}
+
+/// A TCP connection to the other player. Incoming lines are read on a thread of their own, so checking for
+/// messages never holds up a frame.
+pub struct Peer {
+    stream: TcpStream,
+    events: Receiver<PeerEvent>,
+}
+
+impl Peer {
+    pub fn new(stream: TcpStream) -> std::io::Result<Self> {
+        // Inputs are tiny and needed right away, so don't let them sit in a buffer
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+
+        let (sender, events) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str(&line) {
+                    Ok(message) => {
+                        if sender.send(PeerEvent::Message(message)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            let _ = sender.send(PeerEvent::Closed);
+        });
+
+        Ok(Peer { stream, events })
+    }
+
+    pub fn send(&mut self, message: &NetMessage) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.stream.write_all(line.as_bytes())
+    }
+
+    /// The next thing heard from the other side, if anything has arrived
+    pub fn try_recv(&self) -> Option<PeerEvent> {
+        match self.events.try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(PeerEvent::Closed),
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<PeerEvent> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(PeerEvent::Closed),
+        }
+    }
+}
+
+/// Where messages to the other player go and theirs come from: a `Peer`, or a simulated connection for testing
+pub trait Transport {
+    fn send(&mut self, message: &NetMessage) -> std::io::Result<()>;
+    fn try_recv(&mut self) -> Option<PeerEvent>;
+}
+
+impl Transport for Peer {
+    fn send(&mut self, message: &NetMessage) -> std::io::Result<()> {
+        Peer::send(self, message)
+    }
+
+    fn try_recv(&mut self) -> Option<PeerEvent> {
+        Peer::try_recv(self)
+    }
+}
+
+/// Listens on `port` until the other player connects. `keep_waiting` is called about once a frame in the meantime,
+/// e.g. to draw a waiting screen, and gives up with `Ok(None)` once it returns false.
+pub fn host(port: u16, keep_waiting: impl FnMut() -> bool) -> std::io::Result<Option<Peer>> {
+    accept_peer(&TcpListener::bind(("0.0.0.0", port))?, keep_waiting)
+}
+
+/// `host` on a listener that's already bound, e.g. to port 0 so the system picks a free one
+pub fn accept_peer(listener: &TcpListener, mut keep_waiting: impl FnMut() -> bool) -> std::io::Result<Option<Peer>> {
+    listener.set_nonblocking(true)?;
+
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Peer::new(stream).map(Some);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if !keep_waiting() {
+                    return Ok(None);
+                }
+                std::thread::sleep(Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Connects to a host at `address`, e.g. `192.168.1.20:7777`
+pub fn connect(address: &str) -> std::io::Result<Peer> {
+    let timeout = Duration::from_secs(CONNECT_TIMEOUT_SECONDS);
+    let mut last_error = std::io::Error::new(ErrorKind::InvalidInput, format!("No address found for {}", address));
+
+    for socket_address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_address, timeout) {
+            Ok(stream) => return Peer::new(stream),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// Tells a freshly connected client how the match is played, and checks it runs the same simulation
+pub fn greet_client(peer: &mut Peer, settings: MatchSettings) -> std::io::Result<()> {
+    peer.send(&NetMessage::Hello { version: NET_PROTOCOL_VERSION, settings })?;
+    receive_hello(peer).map(|_| ())
+}
+
+/// The client's half of `greet_client`, returning the host's settings
+pub fn greet_host(peer: &mut Peer) -> std::io::Result<MatchSettings> {
+    let settings = receive_hello(peer)?;
+    peer.send(&NetMessage::Hello { version: NET_PROTOCOL_VERSION, settings })?;
+    Ok(settings)
+}
+
+fn receive_hello(peer: &Peer) -> std::io::Result<MatchSettings> {
+    match peer.recv_timeout(Duration::from_secs(NET_TIMEOUT_SECONDS)) {
+        Some(PeerEvent::Message(NetMessage::Hello { version, settings })) => {
+            if version != NET_PROTOCOL_VERSION {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("The other player runs protocol version {}, this is version {}", version, NET_PROTOCOL_VERSION),
+                ));
+            }
+            Ok(settings)
+        }
+        Some(PeerEvent::Message(_)) => Err(std::io::Error::new(ErrorKind::InvalidData, "Expected a hello from the other player")),
+        Some(PeerEvent::Closed) => Err(std::io::Error::new(ErrorKind::ConnectionAborted, "The other player hung up")),
+        None => Err(std::io::Error::new(ErrorKind::TimedOut, "The other player never said hello")),
+    }
+}
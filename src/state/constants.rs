@@ -5,6 +5,7 @@ pub mod graphics {
     pub const ART_HEIGHT: usize = 224;
     pub const SNAKE_BODY_WIDTH: f32 = 6.0;
     pub const SNAKE_BODY_HEIGHT: f32 = 8.0;
+    pub const WORM_SKIN_TINTS: [u32; 4] = [0xFFFFFF, 0xFF7070, 0x70B0FF, 0xFFE060]; // First one leaves the worm as drawn
 }
 
 pub mod physics {
@@ -15,31 +16,155 @@ pub mod physics {
     pub const COLLISION_TOLERANCE: f32 = 1.0;
 }
 
+pub mod gym {
+    // The grid observation has a cell per worm step across the arena
+    pub const GRID_WIDTH: usize = 41;
+    pub const GRID_HEIGHT: usize = 27;
+    pub const GRID_CHANNELS: usize = 5;
+    pub const FEATURE_COUNT: usize = 14;
+    pub const HEADLESS_DELTA_TIME: f32 = 1.0 / 60.0;
+    pub const DEATH_REWARD: f32 = -1.0;
+    // Gives up on a step that somehow never sees the worm move, rather than hanging
+    pub const MAX_FRAMES_PER_STEP: usize = 600;
+    pub const TOURNAMENT_SEEDS: [u64; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    pub const TOURNAMENT_GAMES: usize = 10; // Per contestant, going round the seed list
+    pub const TOURNAMENT_MAX_STEPS: u64 = 5000; // Worm moves before a game is called, about eight minutes of play
+}
+
+pub mod net {
+    pub const NET_PROTOCOL_VERSION: u32 = 4; // Both sides must speak the same version, bumped whenever the simulation changes
+    pub const DEFAULT_INPUT_DELAY: u64 = 3; // Ticks between a key press and the worm turning in lockstep, hiding the trip to the other player
+    pub const ROLLBACK_INPUT_DELAY: u64 = 1; // Rollback only needs a tick of delay, guessing at inputs that are late anyway
+    pub const MAX_PREDICTION_TICKS: u64 = 10; // Most ticks rollback runs ahead of the other player's last known input
+    pub const HASH_INTERVAL: u64 = 30; // Ticks between comparing state hashes with the other player
+    pub const HASH_MEMORY_TICKS: u64 = 600; // Hashes the other player never answered, e.g. lost on the way, are forgotten after this long
+    pub const NET_TIMEOUT_SECONDS: u64 = 5; // The other player is given up on after this long without their input
+    pub const CONNECT_TIMEOUT_SECONDS: u64 = 5;
+    pub const HARNESS_FRAMES: u64 = 3600; // A minute of play unless given on the command line
+    pub const HARNESS_LATENCY_MS: u64 = 80;
+    pub const HARNESS_SETTLE_FRAMES: u64 = 600; // Most frames the harness waits after a run for both peers to agree on where the game is
+    pub const STALL_NOTICE_MILLIS: u64 = 500; // Waiting on the other player this long puts a notice on screen
+    pub const HEADLESS_ACCEPT_SECONDS: u64 = 60; // A headless host gives up on anyone joining after this long
+}
+
 pub mod text {
     pub const SCORE: &str = "Score: ";
     pub const SELECT_PERK: &str = "Select Perk";
     pub const PERK_NEED_4_SPEED: (&str, &str) = ("Need 4 Speed", "+25% movement speed");
     pub const PERK_HUNGRY_WORM: (&str, &str) = ("Hungry Worm", "2x score from food");
-    pub const PERK_CURSE_OF_GLOSSY: (&str, &str) = ("Curse of Glossy", "Death by shiny things");
+    pub const PERK_TASTY_SNACKS: (&str, &str) = ("Tasty Snacks", "+50 score from food");
+    pub const PERK_SLIM_WORM: (&str, &str) = ("Slim Worm", "Shed 3 body segments");
+    pub const PERK_SLOW_WORM: (&str, &str) = ("Slow Worm", "-15% movement speed");
+    pub const PERK_LUCKY_WORM: (&str, &str) = ("Lucky Worm", "One more perk on offer");
+    pub const PERK_IRON_WORM: (&str, &str) = ("Iron Worm", "Invincible for 10s");
+    pub const PERK_BULLET_TIME: (&str, &str) = ("Bullet Time", "Slow motion for 8s");
+    pub const PERK_GHOST_WORM: (&str, &str) = ("Ghost Worm", "Pass through self for 12s");
+    pub const PERK_FOOD_MAGNET: (&str, &str) = ("Food Magnet", "Pull food in for 15s");
+    pub const PERK_CURSE_OF_GLOSSY: (&str, &str) = ("Curse of Glossy", "3x score, shiny things kill");
+    pub const REROLL_PERKS: &str = "R: Reroll -";
+    pub const BEST: &str = "Best: ";
+    pub const BUILD: &str = "Build";
+    pub const NO_PERKS_TAKEN: &str = "No perks taken";
+    pub const PAUSED: &str = "Paused";
+    pub const GAME_OVER_HINT: &str = "Tab: build  Space: retry";
+    pub const PAUSE_HINT: &str = "Tab: build  P: resume";
+    pub const ROUND_DRAW: &str = "Draw";
+    pub const ROUND_WINNER: [&str; 2] = ["P1 takes the round", "P2 takes the round"];
+    pub const MATCH_WINNER: [&str; 2] = ["P1 wins the match", "P2 wins the match"];
+    pub const MATCH_OVER_HINT: &str = "Space: rematch";
+    pub const TITLE: &str = "Space Worm";
+    pub const TITLE_HINT: &str = "Space: play  Esc: quit";
+    pub const DEMO: &str = "Demo";
+    pub const DEMO_HINT: &str = "Press any key";
+    pub const NET_WAITING_FOR_PEER: &str = "Waiting for P2 on port ";
+    pub const NET_CONNECTING: &str = "Connecting to ";
+    pub const NET_CANCEL_HINT: &str = "Esc: cancel";
+    pub const NET_STALLED: &str = "Waiting for the other player";
+    pub const NET_DISCONNECTED: &str = "The other player left";
+    pub const NET_DESYNC: &str = "Out of sync at tick ";
+    pub const NET_MISSING_SNAPSHOT: &str = "Can't go back to tick ";
+    pub const NET_QUIT_HINT: &str = "Esc: quit";
+    pub const NET_REMATCH_HINT: &str = "Rematch coming up";
 }
 
 pub mod audio {
+    // How long startup waits for the output device to start pulling samples
+    pub const AUDIO_READY_TIMEOUT_MS: u64 = 1000;
 
     // - - - - - - - - - - - - - - | MUSIC | - - - - - - - - - - - - - -
     pub const MUSIC_0_FILE: &str = "assets/audio/music_0.mp3";
     pub const MUSIC_1_FILE: &str = "assets/audio/music_1.mp3";
+    pub const MUSIC_CROSSFADE_MS: u64 = 1500;
+    // Gameplay music plays up to this much faster at full intensity, easing in over roughly the response time
+    pub const MUSIC_MAX_SPEEDUP: f32 = 0.12;
+    pub const MUSIC_INTENSITY_RESPONSE_MS: u64 = 2000;
+    // Music drops to this share of its volume while jingles and stings play
+    pub const MUSIC_DUCK_LEVEL: f32 = 0.3;
+    pub const MUSIC_DUCK_FADE_MS: u64 = 200;
+    pub const MUSIC_DUCK_RELEASE_MS: u64 = 600;
 
 
     // - - - - - - - - - - - - - - | FX | - - - - - - - - - - - - - -
+    pub const SFX_VOICE_COUNT: usize = 8;
+    // How far towards either speaker positioned effects are panned, 1.0 being fully to one side
+    pub const SFX_PAN_WIDTH: f32 = 0.8;
     pub const NEW_PERK_FILE: &str = "assets/audio/new_perk.mp3";
     pub const NEED_FOR_SPEED_PERK_CHOSEN_FILE: &str = "assets/audio/need_for_speed.mp3";
     pub const HUNGRY_WORM_PERK_CHOSEN_FILE: &str = "assets/audio/hungry_worm.mp3";
+    pub const SLOW_DOWN_PERK_CHOSEN_FILE: &str = "assets/audio/slow_down.mp3";
+    pub const LUCKY_PERK_CHOSEN_FILE: &str = "assets/audio/lucky.mp3";
+    pub const POWER_UP_PERK_CHOSEN_FILE: &str = "assets/audio/power_up.mp3"; // Shared by the perks with a timed effect
+    pub const CURSED_PERK_CHOSEN_FILE: &str = "assets/audio/cursed.mp3";
 
     pub const SNAKE_EAT_FOOD_FILE: &str = "assets/audio/eat.mp3";
     pub const GAME_OVER_FILE: &str = "assets/audio/game_over.mp3";
+
+    // Synthesizer parameters for effects that have no audio file
+    pub const SFX_DEFINITIONS_FILE: &str = "assets/audio/sfx.ini";
 }
 
 pub mod state {
     pub const FRAME_RATE_SLEEP_DURATION: u64 = 16; // 16 ms for ~60 FPS
+    pub const DEFAULT_MOVE_INTERVAL: f32 = 0.1; // Seconds between worm moves before any perks, 10 moves per second
+    pub const START_LENGTH: usize = 3; // Segments a worm starts every level with, head included
+    pub const PERK_SCORE_THRESHOLDS: [u32; 3] = [1000, 2500, 5000]; // Scores at which perks become available, after which the last gap repeats
+    pub const PERK_OFFER_COUNT: usize = 2; // Perks offered per perk screen, before any Lucky Worm
+    pub const MAX_PERK_OFFER_COUNT: usize = 4;
+    pub const PERK_REROLL_COST: u32 = 250;
+    pub const MAX_DELTA_TIME: f32 = 0.1; // Longer frames, e.g. after a blocking screen, count as this much simulation time
+    pub const INVINCIBILITY_SECONDS: f32 = 10.0;
+    pub const SLOW_MOTION_SECONDS: f32 = 8.0;
+    pub const SLOW_MOTION_TIME_SCALE: f32 = 0.5;
+    pub const GHOST_SECONDS: f32 = 12.0;
+    pub const FOOD_MAGNET_SECONDS: f32 = 15.0;
+    pub const FOOD_MAGNET_SPEED: f32 = 30.0; // Pixels per second the food is pulled towards the head
+    pub const GLOSSY_SPAWN_INTERVAL: f32 = 4.0; // Seconds between shiny hazards appearing
+    pub const GLOSSY_HAZARD_LIFETIME: f32 = 12.0;
+    pub const GLOSSY_HAZARDS_PER_STACK: usize = 3; // Most shiny hazards around at once, per Curse of Glossy taken
+    pub const GLOSSY_SAFE_DISTANCE: f32 = 40.0; // Shiny hazards never appear this close to the head
+    pub const GLOSSY_CONTACT_DISTANCE: f32 = 6.0;
+    pub const MAX_FOOD_ITEMS: usize = 3;
+    pub const FOOD_SPAWN_INTERVAL: f32 = 3.0; // Seconds between extra food items appearing
+    pub const FOOD_BLINK_SECONDS: f32 = 2.0; // Expiring food blinks for this long before it's gone
+    pub const SPEED_RUSH_SECONDS: f32 = 5.0;
+    pub const SPEED_RUSH_TIME_SCALE: f32 = 1.5;
+    pub const PREY_FLEE_DISTANCE: f32 = 40.0; // Prey runs from the head once it's this close
+    pub const PREY_FLEE_SPEEDUP: f32 = 1.8; // Prey moves this much faster while fleeing
+    pub const PREY_TURN_RATE: f32 = 3.0; // Most radians per second wandering prey turns by
+    pub const COMBO_WINDOW_SECONDS: f32 = 2.5; // Eating again within this long raises the combo, idling this long lowers it
+    pub const MAX_COMBO: u32 = 5;
+    pub const SCORE_POPUP_SECONDS: f32 = 1.0;
+    pub const SCORE_POPUP_RISE: f32 = 20.0; // Pixels a score popup floats up over its lifetime
+    pub const BASE_FOOD_SCORE: u32 = 100; // What food is worth before perks
+    pub const PLAYER_START: (f32, f32) = (40.0, 150.0); // Where the player's head starts, heading right
+    pub const PLAYER_TWO_START: (f32, f32) = (220.0, 70.0); // Where player two's head starts in versus, heading left
+    pub const PLAYER_TWO_SKIN: usize = 1;
+    pub const RIVAL_STARTS: [(f32, f32); 3] = [(220.0, 70.0), (220.0, 190.0), (40.0, 40.0)]; // Computer-controlled rivals' heads, facing the middle
+    pub const RIVAL_SKINS: [usize; 3] = [1, 2, 3];
+    pub const VERSUS_ROUNDS_TO_WIN: u32 = 2; // Best of three unless given on the command line
+    pub const ROUND_OVER_SECONDS: u64 = 2; // How long the round result stays up before the next round
+    pub const DEMO_IDLE_SECONDS: f32 = 8.0; // The title screen starts the demo after sitting idle this long
+    pub const HIGH_SCORES_FILE: &str = "highscores.txt";
+    pub const MAX_HIGH_SCORES: usize = 10;
 }
 
@@ -1,33 +1,65 @@
 use crate::state::core::CoreLogic;
-use crate::state::structs::GameState;
+use crate::state::structs::{DeathCause, GameState, Snake, TimedEffect, WormId};
+use crate::state::constants::graphics::{SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
 use crate::state::constants::physics::COLLISION_TOLERANCE;
 
 pub struct CheckSelfCollision;
 
 impl CoreLogic for CheckSelfCollision {
     fn execute(&self, game_state: &mut GameState) {
-        if game_state.player.body.len() <= 1 {
-            return; // Can't collide with self if only head exists
-        }
+        for worm in game_state.living_worms() {
+            let snake = game_state.snake(worm);
+
+            if snake.body.len() <= 1 {
+                continue; // Can't collide with self if only head exists
+            }
 
-        let head_position = &game_state.player.body[0];
+            if snake.has_timed_effect(TimedEffect::Invincibility) || snake.has_timed_effect(TimedEffect::Ghost) {
+                continue; // Passes straight through its own body
+            }
 
-        // Check if head collides with segment (starting from index 1)
-        for i in 1..game_state.player.body.len() {
-            let body_segment = &game_state.player.body[i];
+            let head_position = &snake.body[0];
 
-            // Check if head position overlaps with this body segment
-            if Self::positions_overlap(head_position, body_segment) {
-                game_state.game_over = true;
-                return;
+            // Check if head collides with segment (starting from index 1)
+            if snake.body[1..].iter().any(|body_segment| positions_overlap(head_position, body_segment)) {
+                game_state.kill_worm(worm, DeathCause::SelfCollision);
             }
         }
     }
 }
 
-impl CheckSelfCollision {
-    fn positions_overlap(pos1: &crate::state::structs::Vector2D, pos2: &crate::state::structs::Vector2D) -> bool {
-        // Since we're using exact grid movement, check for exact position match
-        (pos1.x - pos2.x).abs() < COLLISION_TOLERANCE && (pos1.y - pos2.y).abs() < COLLISION_TOLERANCE
+pub struct CheckWormCollisions;
+
+impl CoreLogic for CheckWormCollisions {
+    fn execute(&self, game_state: &mut GameState) {
+        let worms = game_state.living_worms();
+
+        // Find every worm whose head ran into another worm before killing any, so a head-on crash takes out both
+        let crashed: Vec<WormId> = worms
+            .iter()
+            .copied()
+            .filter(|&worm| {
+                let snake = game_state.snake(worm);
+                !snake.has_timed_effect(TimedEffect::Invincibility)
+                    && worms.iter().filter(|&&other| other != worm).any(|&other| head_hits_body(snake, game_state.snake(other)))
+            })
+            .collect();
+
+        for worm in crashed {
+            game_state.kill_worm(worm, DeathCause::WormCollision);
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Worms don't share a grid once they wrap around the arena, so anything closer than a segment counts as a hit
+fn head_hits_body(snake: &Snake, other: &Snake) -> bool {
+    let head_position = &snake.body[0];
+    other.body.iter().any(|segment| {
+        (head_position.x - segment.x).abs() < SNAKE_BODY_WIDTH && (head_position.y - segment.y).abs() < SNAKE_BODY_HEIGHT
+    })
+}
+
+fn positions_overlap(pos1: &crate::state::structs::Vector2D, pos2: &crate::state::structs::Vector2D) -> bool {
+    // Since we're using exact grid movement, check for exact position match
+    (pos1.x - pos2.x).abs() < COLLISION_TOLERANCE && (pos1.y - pos2.y).abs() < COLLISION_TOLERANCE
+}
@@ -0,0 +1,34 @@
+use crate::state::constants::state::{COMBO_WINDOW_SECONDS, SCORE_POPUP_SECONDS};
+use crate::state::core::CoreLogic;
+use crate::state::structs::GameState;
+
+pub struct DecayCombo;
+
+impl CoreLogic for DecayCombo {
+    fn execute(&self, game_state: &mut GameState) {
+        if game_state.combo_timer <= 0.0 {
+            return;
+        }
+
+        game_state.combo_timer -= game_state.delta_time;
+
+        // Without food the combo drops one step per window rather than all at once
+        if game_state.combo_timer <= 0.0 && game_state.combo > 1 {
+            game_state.combo -= 1;
+            game_state.combo_timer = COMBO_WINDOW_SECONDS;
+        }
+    }
+}
+
+pub struct UpdateScorePopups;
+
+impl CoreLogic for UpdateScorePopups {
+    fn execute(&self, game_state: &mut GameState) {
+        let delta_time = game_state.delta_time;
+
+        for popup in game_state.score_popups.iter_mut() {
+            popup.age += delta_time;
+        }
+        game_state.score_popups.retain(|popup| popup.age < SCORE_POPUP_SECONDS);
+    }
+}
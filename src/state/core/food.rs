@@ -1,100 +1,287 @@
-use std::time::Instant;
 use crate::state::core::CoreLogic;
 use crate::state::constants::physics::{LOWER_BOUND_X, LOWER_BOUND_Y, UPPER_BOUND_X, UPPER_BOUND_Y};
-use crate::state::structs::{Direction, Food, GameState, Vector2D};
+use crate::state::structs::{Direction, Food, FoodKind, GameMode, GameState, ScorePopup, Snake, TimedEffect, Vector2D, WormId};
 use rand::Rng;
 use crate::audio::manager::SfxId;
 use crate::state::constants::graphics::{SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
+use crate::state::constants::state::{BASE_FOOD_SCORE, COMBO_WINDOW_SECONDS, FOOD_MAGNET_SPEED, FOOD_SPAWN_INTERVAL, MAX_COMBO, MAX_FOOD_ITEMS};
+use crate::state::food::{food_definition, roll_food_kind};
 
 pub struct SpawnFood;
 
 impl CoreLogic for SpawnFood {
     fn execute(&self, game_state: &mut GameState) {
-        if game_state.food.is_active {
-            return;
+        // Age the food that is out and take away whatever has gone uneaten for too long
+        let delta_time = game_state.delta_time;
+        for food in game_state.foods.iter_mut() {
+            food.age += delta_time;
+        }
+        game_state.foods.retain(|food| food_definition(food.kind).lifetime.is_none_or(|lifetime| food.age < lifetime));
+
+        // There is always normal food to go for
+        if !game_state.foods.iter().any(|food| food.kind == FoodKind::Normal) {
+            spawn_food(game_state, FoodKind::Normal);
         }
 
-        game_state.food = Food {
-            position: Vector2D {
-                x: rand::rng().random_range(LOWER_BOUND_X + 10.0..UPPER_BOUND_X - 10.0),
-                y: rand::rng().random_range(LOWER_BOUND_Y + 10.0..UPPER_BOUND_Y - 10.0)
-            },
-            is_active: true,
-            food_sprite_frame_index: 0,
-            food_last_sprite_frame_index_update_time: Instant::now(),
-        };
+        // Every so often an extra item of any kind joins it
+        game_state.food_spawn_timer += delta_time;
+        if game_state.food_spawn_timer >= FOOD_SPAWN_INTERVAL {
+            game_state.food_spawn_timer = 0.0;
+
+            if game_state.foods.len() < MAX_FOOD_ITEMS {
+                let kind = roll_food_kind(&mut game_state.rng);
+                spawn_food(game_state, kind);
+            }
+        }
     }
 }
 
+fn spawn_food(game_state: &mut GameState, kind: FoodKind) {
+    let position = Vector2D {
+        x: game_state.rng.random_range(LOWER_BOUND_X + 10.0..UPPER_BOUND_X - 10.0),
+        y: game_state.rng.random_range(LOWER_BOUND_Y + 10.0..UPPER_BOUND_Y - 10.0)
+    };
+
+    game_state.foods.push(Food {
+        kind,
+        position,
+        age: 0.0,
+        heading: game_state.rng.random_range(0.0..std::f32::consts::TAU),
+        food_sprite_frame_index: 0,
+    });
+}
+
 pub struct CheckIfFoodWasEaten;
 
 impl CoreLogic for CheckIfFoodWasEaten {
     fn execute(&self, game_state: &mut GameState) {
-        if !game_state.food.is_active {
-            return;
-        }
+        // Food is shared, so whichever worm gets there first eats it
+        for worm in game_state.living_worms() {
+            let head_position = game_state.snake(worm).body[0];
+            game_state.snake_mut(worm).food_near = false;
 
-        let head_position = &game_state.player.body[0];
-        let food_position = &game_state.food.position;
+            let mut index = 0;
+            while index < game_state.foods.len() {
+                let food_position = game_state.foods[index].position;
 
-        if (head_position.x - food_position.x).abs() < 24.0 && (head_position.y - food_position.y).abs() < 24.0 {
-            game_state.player.food_near = true;
+                if (head_position.x - food_position.x).abs() < 24.0 && (head_position.y - food_position.y).abs() < 24.0 {
+                    game_state.snake_mut(worm).food_near = true;
 
-            if (head_position.x - food_position.x).abs() < 12.0 && (head_position.y - food_position.y).abs() < 12.0 {
-                game_state.food.is_active = false;
-                game_state.score += game_state.food_score_value;
-
-                // Check if one is eligible for a perk based on the score
-                if game_state.score % game_state.perk_required_score == 0 {
-                    game_state.perk_eligibility = true;
+                    if (head_position.x - food_position.x).abs() < 12.0 && (head_position.y - food_position.y).abs() < 12.0 {
+                        let food = game_state.foods.remove(index);
+                        eat_food(game_state, worm, &food);
+                        continue;
+                    }
                 }
 
-                let tail_position = game_state.player.body.last().unwrap();
-
-                // Add a new segment to the snake's body at the tail position based on the current direction
-                let new_segment = match game_state.player.direction {
-                    Direction::Left => Vector2D {
-                        x: tail_position.x + SNAKE_BODY_WIDTH,
-                        y: tail_position.y,
-                    },
-                    Direction::Right => Vector2D {
-                        x: tail_position.x - SNAKE_BODY_WIDTH,
-                        y: tail_position.y,
-                    },
-                    Direction::Up => Vector2D {
-                        x: tail_position.x,
-                        y: tail_position.y + SNAKE_BODY_HEIGHT,
-                    },
-                    Direction::Down => Vector2D {
-                        x: tail_position.x,
-                        y: tail_position.y - SNAKE_BODY_HEIGHT,
-                    },
-                };
-
-                game_state.player.body.push(new_segment);
-
-                // Play sound effect for eating food
-                game_state.audio_manager.play_sfx(SfxId::Eat)
-                    .expect("Failed to play eat food sound effect");
+                index += 1;
             }
-        } else {
-            game_state.player.food_near = false;
         }
     }
 }
 
+fn eat_food(game_state: &mut GameState, worm: WormId, food: &Food) {
+    let definition = food_definition(food.kind);
+
+    let text = match (worm, game_state.mode) {
+        // Only a solo run has perks and a combo meter
+        (WormId::Player, GameMode::Solo) => {
+            // Eating while the previous bite's combo window is still open raises the combo
+            if game_state.combo_timer > 0.0 {
+                game_state.combo = (game_state.combo + 1).min(MAX_COMBO);
+            }
+            game_state.combo_timer = COMBO_WINDOW_SECONDS;
+
+            let points = game_state.food_score_value * definition.score_multiplier;
+            game_state.add_score(points * game_state.combo);
+
+            if game_state.combo > 1 { format!("+{} x{}", points, game_state.combo) } else { format!("+{}", points) }
+        }
+        (WormId::Player, _) => {
+            let points = BASE_FOOD_SCORE * definition.score_multiplier;
+            game_state.score += points;
+            format!("+{}", points)
+        }
+        (WormId::Rival(index), _) => {
+            let points = BASE_FOOD_SCORE * definition.score_multiplier;
+            game_state.rivals[index].score += points;
+            format!("+{}", points)
+        }
+    };
+    game_state.score_popups.push(ScorePopup { position: food.position, text, age: 0.0 });
+
+    let snake = game_state.snake_mut(worm);
+    if definition.growth >= 0 {
+        for _ in 0..definition.growth {
+            grow_tail(snake);
+        }
+    } else {
+        // Head, one body segment and the tail always stay
+        let length = snake.body.len().saturating_sub(definition.growth.unsigned_abs() as usize).max(3);
+        snake.body.truncate(length);
+    }
+
+    if let Some((effect, seconds)) = definition.timed_effect {
+        snake.start_timed_effect(effect, seconds);
+    }
+
+    // Play sound effect for eating food
+    if let Err(e) = game_state.audio_manager.play_sfx(SfxId::Eat, Some(food.position.x)) {
+        eprintln!("Warning: Failed to play eat food sound effect: {}", e);
+    }
+}
+
+fn grow_tail(snake: &mut Snake) {
+    let tail_position = snake.body.last().unwrap();
+
+    // Add a new segment to the snake's body at the tail position based on the current direction
+    let new_segment = match snake.direction {
+        Direction::Left => Vector2D {
+            x: tail_position.x + SNAKE_BODY_WIDTH,
+            y: tail_position.y,
+        },
+        Direction::Right => Vector2D {
+            x: tail_position.x - SNAKE_BODY_WIDTH,
+            y: tail_position.y,
+        },
+        Direction::Up => Vector2D {
+            x: tail_position.x,
+            y: tail_position.y + SNAKE_BODY_HEIGHT,
+        },
+        Direction::Down => Vector2D {
+            x: tail_position.x,
+            y: tail_position.y - SNAKE_BODY_HEIGHT,
+        },
+    };
+
+    snake.body.push(new_segment);
+}
+
 pub struct AlternateBetweenFoodSpriteFrames;
 
 impl CoreLogic for AlternateBetweenFoodSpriteFrames {
     fn execute(&self, game_state: &mut GameState) {
-        if !game_state.food.is_active {
+        // Each kind of food flips between its two frames at its own pace
+        for food in game_state.foods.iter_mut() {
+            let frame_interval = food_definition(food.kind).frame_interval;
+            food.food_sprite_frame_index = (food.age / frame_interval) as usize % 2;
+        }
+    }
+}
+
+pub struct PullFoodTowardsHead;
+
+impl CoreLogic for PullFoodTowardsHead {
+    fn execute(&self, game_state: &mut GameState) {
+        if !game_state.player.has_timed_effect(TimedEffect::FoodMagnet) {
             return;
         }
 
-        if game_state.food.food_last_sprite_frame_index_update_time.elapsed().as_millis() >= 500 {
-            game_state.food.food_sprite_frame_index = 1 - game_state.food.food_sprite_frame_index;
-            game_state.food.food_last_sprite_frame_index_update_time = Instant::now();
+        let head_position = game_state.player.body[0];
+        let delta_time = game_state.delta_time;
+
+        for food in game_state.foods.iter_mut() {
+            let food_position = &mut food.position;
+            let (dx, dy) = (head_position.x - food_position.x, head_position.y - food_position.y);
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            // Stop short of overshooting the head, eating is left to CheckIfFoodWasEaten
+            let step = (FOOD_MAGNET_SPEED * delta_time).min(distance);
+            if distance > 0.0 {
+                food_position.x += dx / distance * step;
+                food_position.y += dy / distance * step;
+            }
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::backend::NullBackend;
+    use crate::audio::manager::AudioManager;
+    use crate::graphics::sprites::SpriteMaps;
+    use crate::state::constants::state::PLAYER_START;
+
+    #[test]
+    fn eating_food_plays_the_eat_sound_once() {
+        let backend = NullBackend::new();
+        let log = backend.log();
+        let audio_manager = AudioManager::with_backend(Box::new(backend));
+        audio_manager.preload_all_sfx().unwrap();
+
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        let mut game_state = GameState::new(player, SpriteMaps::new(), 0, 0, None, audio_manager, Vec::new(), 1);
+        game_state.restart_level();
+
+        let position = game_state.player.body[0];
+        game_state.foods.push(Food { kind: FoodKind::Normal, position, age: 0.0, heading: 0.0, food_sprite_frame_index: 0 });
+        CheckIfFoodWasEaten.execute(&mut game_state);
+
+        assert!(game_state.foods.is_empty());
+        assert_eq!(log.sfx_count(SfxId::Eat), 1);
+    }
+
+    fn game_state() -> GameState<'static> {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), 1)
+    }
+
+    fn food(kind: FoodKind, position: Vector2D) -> Food {
+        Food { kind, position, age: 0.0, heading: 0.0, food_sprite_frame_index: 0 }
+    }
+
+    /// Puts food of `kind` right under the player's head and lets the player eat it
+    fn feed(game_state: &mut GameState, kind: FoodKind) {
+        let position = game_state.player.body[0];
+        game_state.foods.push(food(kind, position));
+        CheckIfFoodWasEaten.execute(game_state);
+    }
+
+    #[test]
+    fn food_grows_the_worm_by_its_kind() {
+        let mut game_state = game_state();
+
+        feed(&mut game_state, FoodKind::Normal);
+        assert_eq!(game_state.player.body.len(), 4);
+        feed(&mut game_state, FoodKind::Golden);
+        assert_eq!(game_state.player.body.len(), 7);
+        assert!(game_state.foods.is_empty());
+    }
+
+    #[test]
+    fn shrinking_food_never_takes_the_worm_below_three_segments() {
+        let mut game_state = game_state();
+        feed(&mut game_state, FoodKind::Golden);
+
+        feed(&mut game_state, FoodKind::Shrink);
+        assert_eq!(game_state.player.body.len(), 4);
+        feed(&mut game_state, FoodKind::Shrink);
+        assert_eq!(game_state.player.body.len(), 3);
+        feed(&mut game_state, FoodKind::Shrink);
+        assert_eq!(game_state.player.body.len(), 3);
+    }
+
+    #[test]
+    fn food_scores_by_its_kind() {
+        let mut game_state = game_state();
+
+        feed(&mut game_state, FoodKind::Bonus);
+        assert_eq!(game_state.score, game_state.food_score_value * food_definition(FoodKind::Bonus).score_multiplier);
+    }
+
+    #[test]
+    fn uneaten_food_expires_after_its_lifetime() {
+        let mut game_state = game_state();
+        let far_away = Vector2D { x: UPPER_BOUND_X - 20.0, y: UPPER_BOUND_Y - 20.0 };
+        game_state.foods.push(Food { age: 100.0, ..food(FoodKind::Normal, far_away) });
+        game_state.foods.push(Food { age: 5.9, ..food(FoodKind::Bonus, far_away) });
+        game_state.foods.push(Food { age: 5.0, ..food(FoodKind::Golden, far_away) });
+
+        game_state.delta_time = 0.2;
+        SpawnFood.execute(&mut game_state);
+
+        let kinds: Vec<FoodKind> = game_state.foods.iter().map(|food| food.kind).collect();
+        assert_eq!(kinds, vec![FoodKind::Normal, FoodKind::Golden]);
+    }
+}
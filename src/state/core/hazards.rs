@@ -0,0 +1,94 @@
+use crate::state::constants::physics::{LOWER_BOUND_X, LOWER_BOUND_Y, UPPER_BOUND_X, UPPER_BOUND_Y};
+use crate::state::constants::state::{
+    GLOSSY_CONTACT_DISTANCE, GLOSSY_HAZARDS_PER_STACK, GLOSSY_HAZARD_LIFETIME, GLOSSY_SAFE_DISTANCE, GLOSSY_SPAWN_INTERVAL,
+};
+use crate::state::core::CoreLogic;
+use crate::state::structs::{DeathCause, GameState, Hazard, Perk, TimedEffect, Vector2D};
+use rand::Rng;
+
+pub struct SpawnGlossyHazards;
+
+impl CoreLogic for SpawnGlossyHazards {
+    fn execute(&self, game_state: &mut GameState) {
+        let stacks = game_state.perk_stacks(Perk::CurseOfGlossy);
+        if stacks == 0 {
+            return;
+        }
+
+        // Age the hazards that are out and let the old ones fade away
+        let delta_time = game_state.delta_time;
+        for hazard in game_state.hazards.iter_mut() {
+            hazard.age += delta_time;
+        }
+        game_state.hazards.retain(|hazard| hazard.age < GLOSSY_HAZARD_LIFETIME);
+
+        game_state.hazard_spawn_timer += delta_time;
+        if game_state.hazard_spawn_timer < GLOSSY_SPAWN_INTERVAL || game_state.hazards.len() >= GLOSSY_HAZARDS_PER_STACK * stacks {
+            return;
+        }
+        game_state.hazard_spawn_timer = 0.0;
+
+        // Never drop a hazard right in front of a worm, give up for this tick if no spot is found
+        let head_positions: Vec<Vector2D> = game_state.living_worms().into_iter().map(|worm| game_state.snake(worm).body[0]).collect();
+        for _ in 0..10 {
+            let position = Vector2D {
+                x: game_state.rng.random_range(LOWER_BOUND_X + 10.0..UPPER_BOUND_X - 10.0),
+                y: game_state.rng.random_range(LOWER_BOUND_Y + 10.0..UPPER_BOUND_Y - 10.0),
+            };
+
+            let clear_of_heads = head_positions.iter().all(|head_position| {
+                (position.x - head_position.x).abs() > GLOSSY_SAFE_DISTANCE || (position.y - head_position.y).abs() > GLOSSY_SAFE_DISTANCE
+            });
+            if clear_of_heads {
+                game_state.hazards.push(Hazard { position, age: 0.0 });
+                return;
+            }
+        }
+    }
+}
+
+pub struct CheckHazardCollision;
+
+impl CoreLogic for CheckHazardCollision {
+    fn execute(&self, game_state: &mut GameState) {
+        for worm in game_state.living_worms() {
+            let snake = game_state.snake(worm);
+            if snake.has_timed_effect(TimedEffect::Invincibility) {
+                continue;
+            }
+
+            let head_position = &snake.body[0];
+            let touched = game_state.hazards.iter().any(|hazard| {
+                (head_position.x - hazard.position.x).abs() < GLOSSY_CONTACT_DISTANCE
+                    && (head_position.y - hazard.position.y).abs() < GLOSSY_CONTACT_DISTANCE
+            });
+
+            if touched {
+                game_state.kill_worm(worm, DeathCause::Hazard);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::manager::AudioManager;
+    use crate::graphics::sprites::SpriteMaps;
+    use crate::state::constants::state::PLAYER_START;
+    use crate::state::structs::{Direction, Snake, WormId};
+
+    #[test]
+    fn a_rival_running_into_a_hazard_is_out() {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        let mut game_state = GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), 1);
+        game_state.start_versus(3);
+
+        let rival_head = game_state.snake(WormId::Rival(0)).body[0];
+        game_state.hazards.push(Hazard { position: rival_head, age: 0.0 });
+        CheckHazardCollision.execute(&mut game_state);
+
+        assert_eq!(game_state.living_worms(), vec![WormId::Player]);
+        assert!(!game_state.game_over);
+    }
+}
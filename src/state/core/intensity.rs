@@ -0,0 +1,67 @@
+use crate::state::constants::graphics::SNAKE_BODY_WIDTH;
+use crate::state::constants::state::{DEFAULT_MOVE_INTERVAL, START_LENGTH};
+use crate::state::core::CoreLogic;
+use crate::state::structs::{GameState, Snake};
+
+pub struct UpdateMusicIntensity;
+
+impl CoreLogic for UpdateMusicIntensity {
+    fn execute(&self, game_state: &mut GameState) {
+        let intensity = music_intensity(&game_state.player);
+        game_state.audio_manager.set_intensity(intensity);
+    }
+}
+
+/// Rates how hectic the current situation is, from 0.0 to 1.0, based on how fast the worm moves,
+/// how long it has grown and how close its head is to its own body
+pub fn music_intensity(snake: &Snake) -> f32 {
+    // Each Need 4 Speed perk shortens the move interval by 20%
+    let speed = ((DEFAULT_MOVE_INTERVAL / snake.move_interval) - 1.0).clamp(0.0, 1.0);
+
+    // Count growth from the length every worm starts with
+    let length = ((snake.body.len() as f32 - START_LENGTH as f32) / 40.0).clamp(0.0, 1.0);
+
+    // Skip the neck segments, which are always right behind the head
+    let head = snake.body[0];
+    let nearest_body_distance = snake.body.iter()
+        .skip(3)
+        .map(|segment| ((segment.x - head.x).powi(2) + (segment.y - head.y).powi(2)).sqrt())
+        .fold(f32::MAX, f32::min);
+    let danger = (1.0 - nearest_body_distance / (SNAKE_BODY_WIDTH * 5.0)).clamp(0.0, 1.0);
+
+    (0.4 * speed + 0.3 * length + 0.3 * danger).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::structs::{Direction, Vector2D};
+
+    #[test]
+    fn a_fresh_worm_is_calm() {
+        let snake = Snake::new(100.0, 100.0, Direction::Right);
+        assert_eq!(music_intensity(&snake), 0.0);
+    }
+
+    #[test]
+    fn doubling_the_speed_adds_the_full_speed_share() {
+        let mut snake = Snake::new(100.0, 100.0, Direction::Right);
+        snake.move_interval = DEFAULT_MOVE_INTERVAL / 2.0;
+        assert!((music_intensity(&snake) - 0.4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn body_next_to_the_head_is_dangerous() {
+        let mut snake = Snake::new(100.0, 100.0, Direction::Right);
+        snake.body.push(Vector2D { x: 100.0, y: 100.0 });
+        assert!(music_intensity(&snake) >= 0.3);
+    }
+
+    #[test]
+    fn intensity_never_goes_past_one() {
+        let mut snake = Snake::new(100.0, 100.0, Direction::Right);
+        snake.move_interval = DEFAULT_MOVE_INTERVAL / 10.0;
+        snake.body.extend((0..100).map(|_| Vector2D { x: 100.0, y: 100.0 }));
+        assert!(music_intensity(&snake) <= 1.0);
+    }
+}
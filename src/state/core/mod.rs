@@ -6,58 +6,97 @@ pub mod termination;
 pub mod collision;
 mod background;
 mod perks;
+mod pause;
 pub mod snake;
+pub mod intensity;
+pub mod hazards;
+pub mod combo;
+pub mod versus;
 
 use crate::state::structs::GameState;
-use rodio::Sink;
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 pub trait CoreLogic {
     fn execute(&self, game_state: &mut GameState);
 }
 
-pub fn execute_core_logic(game_state: &mut GameState, core_logic_operations: &HashMap<String, Rc<RefCell<dyn CoreLogic>>>) {
+/// Runs every operation once, in the order they were added, so a game replays exactly from its seed
+pub fn execute_core_logic(game_state: &mut GameState, core_logic_operations: &[(&'static str, Rc<RefCell<dyn CoreLogic>>)]) {
     for (_, core_logic_operation) in core_logic_operations.iter() {
         core_logic_operation.borrow().execute(game_state);
     }
 }
 
-pub fn initialize_core_logic_map() -> HashMap<String, Rc<RefCell<dyn CoreLogic>>> {
-    let mut logic_map: HashMap<String, Rc<RefCell<dyn CoreLogic>>> = HashMap::new();
+/// Every operation of a tick, in the order they run: time moves on before anything moves, and nobody
+/// is declared dead or done before all the collisions are in
+pub fn initialize_core_logic_map() -> Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)> {
+    vec![
+        // Game state updates
+        ("UpdateDeltaTime", Rc::new(RefCell::new(tick::UpdateDeltaTime))),
+        ("TickTimedEffects", Rc::new(RefCell::new(tick::TickTimedEffects))),
 
-    // Game state updates
-    logic_map.insert("UpdateDeltaTime".to_string(), Rc::new(RefCell::new(tick::UpdateDeltaTime)));
+        // Movement
+        ("ModifyCoordinatesOfBodyParts", Rc::new(RefCell::new(movement::ModifyCoordinatesOfBodyParts))),
+        ("MovePrey", Rc::new(RefCell::new(movement::MovePrey))),
 
-    // Movement
-    logic_map.insert("ModifyCoordinatesOfBodyParts".to_string(), Rc::new(RefCell::new(movement::ModifyCoordinatesOfBodyParts)));
+        // Bounds checking
+        ("VerticalBounds", Rc::new(RefCell::new(bounds::VerticalBounds))),
+        ("HorizontalBounds", Rc::new(RefCell::new(bounds::HorizontalBounds))),
 
-    // Bounds checking
-    logic_map.insert("VerticalBounds".to_string(), Rc::new(RefCell::new(bounds::VerticalBounds)));
-    logic_map.insert("HorizontalBounds".to_string(), Rc::new(RefCell::new(bounds::HorizontalBounds)));
+        // Food system
+        ("SpawnFood", Rc::new(RefCell::new(food::SpawnFood))),
+        ("CheckIfFoodWasEaten", Rc::new(RefCell::new(food::CheckIfFoodWasEaten))),
+        ("PullFoodTowardsHead", Rc::new(RefCell::new(food::PullFoodTowardsHead))),
+        ("AlternateBetweenFoodSpriteFrames", Rc::new(RefCell::new(food::AlternateBetweenFoodSpriteFrames))),
 
-    // Food system
-    logic_map.insert("SpawnFood".to_string(), Rc::new(RefCell::new(food::SpawnFood)));
-    logic_map.insert("CheckIfFoodWasEaten".to_string(), Rc::new(RefCell::new(food::CheckIfFoodWasEaten)));
-    logic_map.insert("AlternateBetweenFoodSpriteFrames".to_string(), Rc::new(RefCell::new(food::AlternateBetweenFoodSpriteFrames)));
+        // Combo meter
+        ("DecayCombo", Rc::new(RefCell::new(combo::DecayCombo))),
+        ("UpdateScorePopups", Rc::new(RefCell::new(combo::UpdateScorePopups))),
 
-    // Collision detection
-    logic_map.insert("CheckSelfCollision".to_string(), Rc::new(RefCell::new(collision::CheckSelfCollision)));
+        // Collision detection
+        ("CheckSelfCollision", Rc::new(RefCell::new(collision::CheckSelfCollision))),
+        ("CheckWormCollisions", Rc::new(RefCell::new(collision::CheckWormCollisions))),
+        ("CheckHazardCollision", Rc::new(RefCell::new(hazards::CheckHazardCollision))),
 
-    // Snake sprite logic
-    logic_map.insert("AlternateBodySpriteFrameIndex".to_string(), Rc::new(RefCell::new(snake::AlternateBodySpriteFrameIndex)));
-    logic_map.insert("AlternateHeadSpriteFrameIndex".to_string(), Rc::new(RefCell::new(snake::AlternateHeadSpriteFrameIndex)));
+        // Cursed perks
+        ("SpawnGlossyHazards", Rc::new(RefCell::new(hazards::SpawnGlossyHazards))),
 
-    // Background sprite frames
-    logic_map.insert("AlternateBackgroundSpriteFrame".to_string(), Rc::new(RefCell::new(background::AlternateGlobeSpriteFrame)));
-    logic_map.insert("AlternateStarsSpriteFrame".to_string(), Rc::new(RefCell::new(background::AlternateStarsSpriteFrame)));
+        // Snake sprite logic
+        ("AlternateBodySpriteFrameIndex", Rc::new(RefCell::new(snake::AlternateBodySpriteFrameIndex))),
+        ("AlternateHeadSpriteFrameIndex", Rc::new(RefCell::new(snake::AlternateHeadSpriteFrameIndex))),
 
-    // Game over logic
-    logic_map.insert("CheckGameOver".to_string(), Rc::new(RefCell::new(termination::CheckGameOver)));
+        // Background sprite frames
+        ("AlternateBackgroundSpriteFrame", Rc::new(RefCell::new(background::AlternateGlobeSpriteFrame))),
+        ("AlternateStarsSpriteFrame", Rc::new(RefCell::new(background::AlternateStarsSpriteFrame))),
 
-    // Perks
-    logic_map.insert("CheckNewPerk".to_string(), Rc::new(RefCell::new(perks::CheckNewPerk)));
+        // Game over logic
+        ("CheckGameOver", Rc::new(RefCell::new(termination::CheckGameOver))),
 
-    logic_map
+        // Versus rounds
+        ("CheckRoundOver", Rc::new(RefCell::new(versus::CheckRoundOver))),
+
+        // Adaptive music
+        ("UpdateMusicIntensity", Rc::new(RefCell::new(intensity::UpdateMusicIntensity))),
+
+        // Pause menu
+        ("CheckPause", Rc::new(RefCell::new(pause::CheckPause))),
+
+        // Perks
+        ("CheckNewPerk", Rc::new(RefCell::new(perks::CheckNewPerk))),
+    ]
+}
+
+/// The core logic without anything that waits on the keyboard or only matters to the speakers,
+/// for games with no window. Perk picks and game over are left to whoever drives the game.
+pub fn initialize_headless_core_logic_map() -> Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)> {
+    let interactive = ["CheckNewPerk", "CheckPause", "CheckGameOver", "CheckRoundOver", "UpdateMusicIntensity"];
+    initialize_core_logic_map().into_iter().filter(|(name, _)| !interactive.contains(name)).collect()
+}
+
+/// The core logic for one machine's side of an online match, without anything that waits on the keyboard:
+/// both sides pace rounds and draw their screens on their own, outside the simulation they share
+pub fn initialize_online_core_logic_map() -> Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)> {
+    let interactive = ["CheckNewPerk", "CheckPause", "CheckGameOver", "CheckRoundOver"];
+    initialize_core_logic_map().into_iter().filter(|(name, _)| !interactive.contains(name)).collect()
 }
\ No newline at end of file
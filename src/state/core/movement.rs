@@ -1,35 +1,89 @@
-use crate::state::structs::{Direction, GameState};
+use crate::state::structs::{Direction, GameState, Snake, TimedEffect};
 use crate::state::constants::graphics::{SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
+use crate::state::constants::state::{PREY_FLEE_DISTANCE, PREY_FLEE_SPEEDUP, PREY_TURN_RATE, SLOW_MOTION_TIME_SCALE, SPEED_RUSH_TIME_SCALE};
+use crate::state::core::bounds::{wrap_horizontally, wrap_vertically};
 use crate::state::core::CoreLogic;
+use crate::state::food::food_definition;
+use rand::Rng;
 
 pub struct ModifyCoordinatesOfBodyParts;
 
 impl CoreLogic for ModifyCoordinatesOfBodyParts {
     fn execute(&self, game_state: &mut GameState) {
-        // Update timer using stored delta time
-        game_state.player.move_timer += game_state.delta_time;
-
-        // Only move when timer exceeds interval
-        if game_state.player.move_timer >= game_state.player.move_interval {
-            game_state.player.move_timer = 0.0; // Reset timer
-
-            let body_size = game_state.player.body.len();
-            if body_size > 0 {
-                // Move body segments
-                for i in 1..body_size {
-                    // Shift each body segment to the position of the previous segment
-                    // The segment at index [body_size - i] (starting from the tail) gets the value of the segment at [body_size - i - 1]
-                    // This ensures that the "neck" (second segment) gets the position of the "head" (first segment),
-                    // and each subsequent segment follows the position of the segment before it.
-                    game_state.player.body[body_size - i] = game_state.player.body[body_size - i - 1].clone();
-                }
+        let delta_time = game_state.delta_time;
+
+        for snake in game_state.living_snakes_mut() {
+            advance_snake(snake, delta_time);
+        }
+    }
+}
+
+/// Moves `snake` a step in its direction once its move timer is up
+fn advance_snake(snake: &mut Snake, delta_time: f32) {
+    // Update timer using stored delta time, which runs slower in slow motion and faster on a speed rush
+    let mut time_scale = 1.0;
+    if snake.has_timed_effect(TimedEffect::SlowMotion) {
+        time_scale *= SLOW_MOTION_TIME_SCALE;
+    }
+    if snake.has_timed_effect(TimedEffect::SpeedRush) {
+        time_scale *= SPEED_RUSH_TIME_SCALE;
+    }
+    snake.move_timer += delta_time * time_scale;
+
+    // Only move when timer exceeds interval
+    if snake.move_timer >= snake.move_interval {
+        snake.move_timer = 0.0; // Reset timer
+        snake.moves += 1;
+
+        let body_size = snake.body.len();
+        if body_size > 0 {
+            // Move body segments
+            for i in 1..body_size {
+                // Shift each body segment to the position of the previous segment
+                // The segment at index [body_size - i] (starting from the tail) gets the value of the segment at [body_size - i - 1]
+                // This ensures that the "neck" (second segment) gets the position of the "head" (first segment),
+                // and each subsequent segment follows the position of the segment before it.
+                snake.body[body_size - i] = snake.body[body_size - i - 1];
             }
-                match game_state.player.direction {
-                    Direction::Left => game_state.player.body[0].x -= SNAKE_BODY_WIDTH,
-                    Direction::Right => game_state.player.body[0].x += SNAKE_BODY_WIDTH,
-                    Direction::Up => game_state.player.body[0].y -= SNAKE_BODY_HEIGHT,
-                    Direction::Down => game_state.player.body[0].y += SNAKE_BODY_HEIGHT,
-                }
+        }
+        match snake.direction {
+            Direction::Left => snake.body[0].x -= SNAKE_BODY_WIDTH,
+            Direction::Right => snake.body[0].x += SNAKE_BODY_WIDTH,
+            Direction::Up => snake.body[0].y -= SNAKE_BODY_HEIGHT,
+            Direction::Down => snake.body[0].y += SNAKE_BODY_HEIGHT,
+        }
+    }
+}
+
+pub struct MovePrey;
+
+impl CoreLogic for MovePrey {
+    fn execute(&self, game_state: &mut GameState) {
+        let head_position = game_state.player.body[0];
+        let delta_time = game_state.delta_time;
+
+        for food in game_state.foods.iter_mut() {
+            let wander_speed = food_definition(food.kind).wander_speed;
+            if wander_speed <= 0.0 {
+                continue;
             }
+
+            // Run straight away from the head when it gets close, otherwise drift along a slowly turning heading
+            let (dx, dy) = (food.position.x - head_position.x, food.position.y - head_position.y);
+            let speed = if (dx * dx + dy * dy).sqrt() < PREY_FLEE_DISTANCE {
+                food.heading = dy.atan2(dx);
+                wander_speed * PREY_FLEE_SPEEDUP
+            } else {
+                food.heading += game_state.rng.random_range(-PREY_TURN_RATE..PREY_TURN_RATE) * delta_time;
+                wander_speed
+            };
+
+            food.position.x += food.heading.cos() * speed * delta_time;
+            food.position.y += food.heading.sin() * speed * delta_time;
+
+            // Same bounds policy as the worm
+            wrap_vertically(&mut food.position);
+            wrap_horizontally(&mut food.position);
         }
-    }
\ No newline at end of file
+    }
+}
@@ -0,0 +1,36 @@
+use minifb::{Key, KeyRepeat};
+use crate::graphics::render::render_pixel_buffer;
+use crate::graphics::update::draw_pause_screen;
+use crate::state::constants::state::FRAME_RATE_SLEEP_DURATION;
+use crate::state::core::CoreLogic;
+use crate::state::structs::GameState;
+
+pub struct CheckPause;
+
+impl CoreLogic for CheckPause {
+    fn execute(&self, game_state: &mut GameState) {
+        if !game_state.is_key_pressed(Key::P, KeyRepeat::No) {
+            return;
+        }
+
+        game_state.audio_manager.pause_music();
+        let mut show_build = false;
+
+        // Blocks until resumed, simulation time doesn't advance meanwhile as UpdateDeltaTime caps the next frame
+        while game_state.is_window_open() && !game_state.is_key_down(Key::Escape) {
+            if game_state.is_key_pressed(Key::Tab, KeyRepeat::No) {
+                show_build = !show_build;
+            }
+
+            if game_state.is_key_pressed(Key::P, KeyRepeat::No) || game_state.is_key_pressed(Key::Space, KeyRepeat::No) {
+                break;
+            }
+
+            draw_pause_screen(game_state, show_build);
+            render_pixel_buffer(game_state);
+            std::thread::sleep(std::time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+        }
+
+        game_state.audio_manager.resume_music();
+    }
+}
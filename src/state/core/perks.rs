@@ -1,9 +1,11 @@
 use minifb::{Key, KeyRepeat};
+use crate::audio::director::Scene;
 use crate::audio::manager::SfxId;
 use crate::graphics::render::render_pixel_buffer;
 use crate::graphics::update::draw_choose_perk_screen_with_highlight;
-use crate::state::constants::audio::NEW_PERK_FILE;
 use crate::state::core::CoreLogic;
+use crate::state::constants::state::PERK_REROLL_COST;
+use crate::state::perks::{roll_perk_offers, take_perk};
 use crate::state::structs::Perk;
 
 pub struct CheckNewPerk;
@@ -12,17 +14,24 @@ pub struct CheckNewPerk;
 
 impl CoreLogic for CheckNewPerk {
     fn execute(&self, game_state: &mut crate::state::structs::GameState) {
-        if game_state.perk_eligibility {
+        if game_state.pending_perk_picks > 0 {
 
-            // Play new perk music, stop and disable any existing music
-            if game_state.audio_manager.is_music_playing() {
-                game_state.audio_manager.stop_music();
-            }
+            // Draw this screen's offer from the perks still available this run
+            let taken: Vec<Perk> = game_state.perk_history.iter().map(|pick| pick.perk).collect();
+            let mut offers = roll_perk_offers(&taken, game_state.perk_offer_count, &mut game_state.rng);
 
-            game_state.music_disabled = true;
+            // Nothing left to offer, so there is nothing to pick now or later
+            if offers.is_empty() {
+                game_state.pending_perk_picks = 0;
+                return;
+            }
 
-            game_state.audio_manager.play_sfx(SfxId::NewPerk)
-                .expect("Failed to play new perk sound effect");
+            // Crossfade into the perk selection music, ducked under the new perk jingle until it is over
+            game_state.audio_manager.set_scene(Scene::PerkSelect);
+            if let Err(e) = game_state.audio_manager.play_sfx(SfxId::NewPerk, None) {
+                eprintln!("Warning: Failed to play new perk sound effect: {}", e);
+            }
+            game_state.audio_manager.duck_music_while(SfxId::NewPerk);
 
 
             game_state.selected_perk = None;
@@ -36,26 +45,33 @@ impl CoreLogic for CheckNewPerk {
 
             loop {
 
+                // Trade some score for a fresh offer
+                if game_state.is_key_pressed(Key::R, KeyRepeat::No) && game_state.score >= PERK_REROLL_COST {
+                    game_state.score -= PERK_REROLL_COST;
+                    offers = roll_perk_offers(&taken, game_state.perk_offer_count, &mut game_state.rng);
+                    highlighted_perk = highlighted_perk.map(|perk_index| perk_index.min(offers.len() - 1));
+                }
+
                 for (key, direction) in key_perk_map.iter() {
 
                     // Defaults to the first perk if escape is pressed
-                    if game_state.window.is_key_pressed(Key::Escape, KeyRepeat::No) {
+                    if game_state.is_key_pressed(Key::Escape, KeyRepeat::No) {
                         game_state.selected_perk = Some(0);
                         perk_selected = true;
                     }
 
                     // Keys A and D will map to an index used to highlight and ultimately decide perk
-                    if game_state.window.is_key_down(*key) {
+                    if game_state.is_key_down(*key) {
                         if let Some(current) = highlighted_perk {
-                            let new_perk = (current as isize + direction).clamp(1, 2) as usize;
+                            let new_perk = (current as isize + direction).clamp(0, offers.len() as isize - 1) as usize;
                             highlighted_perk = Some(new_perk);
                         } else {
-                            highlighted_perk = Some(1); // Default to the first perk if none is highlighted
+                            highlighted_perk = Some(0); // Default to the first perk if none is highlighted
                         }
                     }
 
                     // Lock in choice, with default being the first perk as is the case for escape
-                    if game_state.window.is_key_pressed(Key::Space, KeyRepeat::No) {
+                    if game_state.is_key_pressed(Key::Space, KeyRepeat::No) {
                         if let Some(perk) = highlighted_perk {
                             game_state.selected_perk = Some(perk);
                             perk_selected = true;
@@ -64,25 +80,23 @@ impl CoreLogic for CheckNewPerk {
                     }
                 }
 
-                draw_choose_perk_screen_with_highlight(game_state, highlighted_perk);
+                draw_choose_perk_screen_with_highlight(game_state, &offers, highlighted_perk);
                 render_pixel_buffer(game_state);
+                game_state.audio_manager.update();
 
 
                 if perk_selected {
-                    game_state.perk_eligibility = false;
-
-                    match game_state.selected_perk {
-                        Some(1) => {
-                            game_state.player.move_interval *= 0.8;
-                            game_state.perk_history.insert(game_state.score, Perk::SpeedBoost);
-                        },
-                        Some(2) => {
-                             game_state.food_score_value *= 2;
-                            game_state.perk_history.insert(game_state.score, Perk::DoubleScore);
-                        }
-                        _ => {}
+                    // Any further queued picks get their own screen on the next tick
+                    game_state.pending_perk_picks -= 1;
+
+                    let chosen = game_state.selected_perk.and_then(|perk_index| offers.get(perk_index));
+                    if let Some(&perk) = chosen {
+                        take_perk(game_state, perk);
                     }
 
+                    // Resume the gameplay music where it left off
+                    game_state.audio_manager.set_scene(Scene::Gameplay);
+
                     std::thread::sleep(std::time::Duration::from_millis(200));
                     break;
                 }
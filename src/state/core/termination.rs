@@ -1,20 +1,33 @@
-use crate::graphics::update::draw_game_over_screen;
+use crate::audio::director::Scene;
+use crate::audio::manager::SfxId;
+use crate::graphics::update::{draw_game_over_build, draw_game_over_screen, draw_game_over_summary};
+use crate::state::constants::state::FRAME_RATE_SLEEP_DURATION;
 use crate::state::core::CoreLogic;
-use crate::state::structs::GameState;
+use crate::state::highscores::{record_high_score, HighScoreEntry};
+use crate::state::structs::{GameMode, GameState};
 use crate::graphics::render::render_pixel_buffer;
+use minifb::{Key, KeyRepeat};
 
 pub struct CheckGameOver;
 
 impl CoreLogic for CheckGameOver {
     fn execute(&self, game_state: &mut GameState) {
 
-        if game_state.game_over {
+        // A versus round ends in core::versus instead, and the demo just starts over
+        if game_state.game_over && game_state.mode == GameMode::Solo {
+            game_state.audio_manager.set_scene(Scene::GameOver);
+            if let Err(e) = game_state.audio_manager.play_sfx(SfxId::GameOver, Some(game_state.player.body[0].x)) {
+                eprintln!("Warning: Failed to play game over sound effect: {}", e);
+            }
+            game_state.audio_manager.duck_music_while(SfxId::GameOver);
+
             let mut frame = 0;
             let mut darkness_factor = Some(0.5); // Initial darkness factor
 
             while frame < 8 {
                 draw_game_over_screen(game_state, frame, darkness_factor);
                 render_pixel_buffer(game_state);
+                game_state.audio_manager.update();
 
                 // Sleep for 200 ms
                 std::thread::sleep(std::time::Duration::from_millis(200));
@@ -22,7 +35,39 @@ impl CoreLogic for CheckGameOver {
                 frame += 1;
                 darkness_factor = darkness_factor.map(|d| (d + 0.1).min(0.8)); // Increase darkness by 10%, cap at 0.8
             }
+
+            // Remember the run's build alongside its score
+            record_high_score(&mut game_state.high_scores, HighScoreEntry {
+                score: game_state.score,
+                build: game_state.perk_history.clone(),
+            });
+
+            // Tab switches between the score and the build, space starts the next run
+            let mut show_build = false;
+            while game_state.is_window_open() && !game_state.is_key_down(Key::Escape) {
+                if game_state.is_key_pressed(Key::Tab, KeyRepeat::No) {
+                    show_build = !show_build;
+                }
+
+                if game_state.is_key_pressed(Key::Space, KeyRepeat::No) || game_state.is_key_pressed(Key::Enter, KeyRepeat::No) {
+                    break;
+                }
+
+                if show_build {
+                    draw_game_over_build(game_state);
+                } else {
+                    draw_game_over_summary(game_state);
+                }
+                render_pixel_buffer(game_state);
+                game_state.audio_manager.update();
+                std::thread::sleep(std::time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+            }
+
             game_state.restart_level();
+
+            // A new run starts the gameplay playlist from the top
+            game_state.audio_manager.restart_scene(Scene::Gameplay);
+            game_state.audio_manager.set_scene(Scene::Gameplay);
         }
         }
 }
\ No newline at end of file
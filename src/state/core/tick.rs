@@ -1,3 +1,4 @@
+use crate::state::constants::state::MAX_DELTA_TIME;
 use crate::state::core::CoreLogic;
 use crate::state::structs::GameState;
 use std::time::Instant;
@@ -8,12 +9,102 @@ impl CoreLogic for UpdateDeltaTime {
     fn execute(&self, game_state: &mut GameState) {
         let current_time = Instant::now();
 
-        game_state.delta_time = if let Some(last_time) = game_state.last_frame_time {
-            current_time.duration_since(last_time).as_secs_f32()
+        game_state.delta_time = if let Some(fixed_delta_time) = game_state.fixed_delta_time {
+            fixed_delta_time
+        } else if let Some(last_time) = game_state.last_frame_time {
+            current_time.duration_since(last_time).as_secs_f32().min(MAX_DELTA_TIME)
         } else {
             1.0 / 60.0
         };
 
         game_state.last_frame_time = Some(current_time);
+        game_state.run_time += game_state.delta_time;
     }
-}
\ No newline at end of file
+}
+
+pub struct TickTimedEffects;
+
+impl CoreLogic for TickTimedEffects {
+    fn execute(&self, game_state: &mut GameState) {
+        let delta_time = game_state.delta_time;
+
+        // Effects are only ever looked up while active, so dropping one is all it takes to revert it
+        for snake in game_state.living_snakes_mut() {
+            for active in snake.active_effects.iter_mut() {
+                active.remaining -= delta_time;
+            }
+            snake.active_effects.retain(|active| active.remaining > 0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::manager::AudioManager;
+    use crate::graphics::sprites::SpriteMaps;
+    use crate::state::constants::state::PLAYER_START;
+    use crate::state::core::collision::CheckSelfCollision;
+    use crate::state::structs::{Direction, Snake, TimedEffect};
+
+    fn game_state() -> GameState<'static> {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), 1)
+    }
+
+    fn tick(game_state: &mut GameState, seconds: f32) {
+        game_state.delta_time = seconds;
+        TickTimedEffects.execute(game_state);
+    }
+
+    fn remaining(snake: &Snake, effect: TimedEffect) -> Option<f32> {
+        snake.active_effects.iter().find(|active| active.effect == effect).map(|active| active.remaining)
+    }
+
+    #[test]
+    fn restarting_a_running_effect_tops_it_up_instead_of_stacking() {
+        let mut game_state = game_state();
+        game_state.player.start_timed_effect(TimedEffect::SlowMotion, 5.0);
+        tick(&mut game_state, 3.0);
+
+        game_state.player.start_timed_effect(TimedEffect::SlowMotion, 5.0);
+        assert_eq!(remaining(&game_state.player, TimedEffect::SlowMotion), Some(5.0));
+        assert_eq!(game_state.player.active_effects.len(), 1);
+
+        // A shorter effect never cuts a longer one short
+        game_state.player.start_timed_effect(TimedEffect::SlowMotion, 1.0);
+        assert_eq!(remaining(&game_state.player, TimedEffect::SlowMotion), Some(5.0));
+    }
+
+    #[test]
+    fn different_effects_run_down_side_by_side() {
+        let mut game_state = game_state();
+        game_state.player.start_timed_effect(TimedEffect::SlowMotion, 2.0);
+        game_state.player.start_timed_effect(TimedEffect::Ghost, 4.0);
+
+        tick(&mut game_state, 3.0);
+
+        assert!(!game_state.player.has_timed_effect(TimedEffect::SlowMotion));
+        assert_eq!(remaining(&game_state.player, TimedEffect::Ghost), Some(1.0));
+    }
+
+    #[test]
+    fn an_effect_is_reverted_once_it_runs_out() {
+        let mut game_state = game_state();
+        game_state.player.start_timed_effect(TimedEffect::Ghost, 1.0);
+
+        // Head on the tail, which a ghost passes straight through
+        let tail = *game_state.player.body.last().unwrap();
+        game_state.player.body[0] = tail;
+        CheckSelfCollision.execute(&mut game_state);
+        assert!(!game_state.game_over);
+
+        tick(&mut game_state, 0.6);
+        assert!(game_state.player.has_timed_effect(TimedEffect::Ghost));
+        tick(&mut game_state, 0.6);
+        assert!(!game_state.player.has_timed_effect(TimedEffect::Ghost));
+
+        CheckSelfCollision.execute(&mut game_state);
+        assert!(game_state.game_over);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::graphics::render::render_pixel_buffer;
+use crate::graphics::update::{draw_match_over_screen, draw_round_over_screen};
+use crate::state::constants::state::{FRAME_RATE_SLEEP_DURATION, ROUND_OVER_SECONDS};
+use crate::state::constants::text::MATCH_OVER_HINT;
+use crate::state::core::CoreLogic;
+use crate::state::structs::{GameMode, GameState};
+use minifb::{Key, KeyRepeat};
+
+/// How a versus round ended. Players are 0 for the player's worm and 1 for the first rival.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundOutcome {
+    /// `None` for a draw
+    pub winner: Option<usize>,
+    /// Set once the round's winner has won enough rounds to take the match
+    pub match_winner: Option<usize>,
+}
+
+pub struct CheckRoundOver;
+
+impl CoreLogic for CheckRoundOver {
+    fn execute(&self, game_state: &mut GameState) {
+        let Some(outcome) = settle_round(game_state) else {
+            return;
+        };
+
+        show_round_over(game_state, outcome.winner);
+
+        if let Some(match_winner) = outcome.match_winner {
+            // Space starts a rematch from nil-nil
+            while game_state.is_window_open() && !game_state.is_key_down(Key::Escape) {
+                if game_state.is_key_pressed(Key::Space, KeyRepeat::No) || game_state.is_key_pressed(Key::Enter, KeyRepeat::No) {
+                    break;
+                }
+
+                draw_match_over_screen(game_state, match_winner, MATCH_OVER_HINT);
+                render_pixel_buffer(game_state);
+                game_state.audio_manager.update();
+                std::thread::sleep(std::time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+            }
+        }
+
+        start_next_round(game_state, outcome);
+    }
+}
+
+/// Scores a versus round once at most one worm is left, the last worm alive taking it and worms going down together
+/// making it a draw. Returns `None` while the round is still on.
+pub fn settle_round(game_state: &mut GameState) -> Option<RoundOutcome> {
+    let GameMode::Versus { rounds_to_win } = game_state.mode else {
+        return None;
+    };
+
+    let player_one_alive = !game_state.game_over;
+    let player_two_alive = game_state.rivals.iter().any(|rival| rival.alive);
+    if player_one_alive && player_two_alive {
+        return None;
+    }
+
+    let winner = match (player_one_alive, player_two_alive) {
+        (true, false) => Some(0),
+        (false, true) => Some(1),
+        _ => None,
+    };
+    if let Some(winner) = winner {
+        game_state.round_wins[winner] += 1;
+    }
+
+    let match_winner = game_state.round_wins.iter().position(|&wins| wins >= rounds_to_win);
+    Some(RoundOutcome { winner, match_winner })
+}
+
+/// Keeps the round's result up for a moment
+pub fn show_round_over(game_state: &mut GameState, winner: Option<usize>) {
+    let round_over_frames = ROUND_OVER_SECONDS * 1000 / FRAME_RATE_SLEEP_DURATION;
+    for _ in 0..round_over_frames {
+        if !game_state.is_window_open() {
+            break;
+        }
+
+        draw_round_over_screen(game_state, winner);
+        render_pixel_buffer(game_state);
+        game_state.audio_manager.update();
+        std::thread::sleep(std::time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+}
+
+/// A decided match starts over from nil-nil
+pub fn start_next_round(game_state: &mut GameState, outcome: RoundOutcome) {
+    if outcome.match_winner.is_some() {
+        game_state.round_wins = [0, 0];
+    }
+    game_state.restart_level();
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::manager::AudioManager;
+    use crate::graphics::sprites::SpriteMaps;
+    use crate::state::constants::state::PLAYER_START;
+    use crate::state::core::collision::CheckWormCollisions;
+    use crate::state::structs::{Direction, Snake, WormId};
+
+    fn versus(rounds_to_win: u32) -> GameState<'static> {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        let mut game_state = GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), 1);
+        game_state.start_versus(rounds_to_win);
+        game_state
+    }
+
+    #[test]
+    fn the_round_goes_on_while_both_worms_live() {
+        let mut game_state = versus(3);
+        assert_eq!(settle_round(&mut game_state), None);
+    }
+
+    #[test]
+    fn the_last_worm_alive_wins_the_round() {
+        let mut game_state = versus(3);
+        game_state.rivals[0].alive = false;
+
+        assert_eq!(settle_round(&mut game_state), Some(RoundOutcome { winner: Some(0), match_winner: None }));
+        assert_eq!(game_state.round_wins, [1, 0]);
+    }
+
+    #[test]
+    fn a_head_on_crash_is_a_draw() {
+        let mut game_state = versus(3);
+        let player_head = game_state.player.body[0];
+        game_state.rivals[0].snake.body[0] = player_head;
+
+        CheckWormCollisions.execute(&mut game_state);
+
+        assert!(game_state.game_over);
+        assert!(!game_state.rivals[0].alive);
+        assert_eq!(game_state.living_worms(), Vec::<WormId>::new());
+        assert_eq!(settle_round(&mut game_state), Some(RoundOutcome { winner: None, match_winner: None }));
+        assert_eq!(game_state.round_wins, [0, 0]);
+    }
+
+    #[test]
+    fn the_match_is_won_at_rounds_to_win_and_then_starts_over() {
+        let mut game_state = versus(2);
+
+        game_state.game_over = true;
+        let first = settle_round(&mut game_state).unwrap();
+        assert_eq!(first, RoundOutcome { winner: Some(1), match_winner: None });
+        start_next_round(&mut game_state, first);
+
+        game_state.game_over = true;
+        let second = settle_round(&mut game_state).unwrap();
+        assert_eq!(second, RoundOutcome { winner: Some(1), match_winner: Some(1) });
+        assert_eq!(game_state.round_wins, [0, 2]);
+
+        start_next_round(&mut game_state, second);
+        assert_eq!(game_state.round_wins, [0, 0]);
+        assert_eq!(game_state.living_worms(), vec![WormId::Player, WormId::Rival(0)]);
+    }
+}
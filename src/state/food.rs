@@ -0,0 +1,116 @@
+use crate::state::constants::state::SPEED_RUSH_SECONDS;
+use crate::state::structs::{FoodKind, TimedEffect};
+use rand::Rng;
+
+/// Everything the game needs to know about a kind of food: how often it shows up,
+/// how long it stays, how it looks and what eating it does
+pub struct FoodDefinition {
+    pub kind: FoodKind,
+    /// Relative chance of this kind being picked when extra food spawns
+    pub spawn_weight: u32,
+    /// Seconds before the food disappears uneaten, `None` meaning it stays until eaten
+    pub lifetime: Option<f32>,
+    /// Multiplies the food score value, which perks may have raised
+    pub score_multiplier: u32,
+    /// Segments gained when eaten, negative values shrink the worm
+    pub growth: i32,
+    /// Seconds each frame of the food sprite is shown for
+    pub frame_interval: f32,
+    /// Color the food sprite is multiplied with, white leaving it as drawn
+    pub tint: u32,
+    /// Effect started when eaten, and for how many seconds it lasts
+    pub timed_effect: Option<(TimedEffect, f32)>,
+    /// Pixels per second the food wanders around at, zero for food that stays put
+    pub wander_speed: f32,
+}
+
+pub static FOOD_CATALOGUE: &[FoodDefinition] = &[
+    FoodDefinition {
+        kind: FoodKind::Normal,
+        spawn_weight: 50,
+        lifetime: None,
+        score_multiplier: 1,
+        growth: 1,
+        frame_interval: 0.5,
+        tint: 0xFFFFFF,
+        timed_effect: None,
+        wander_speed: 0.0,
+    },
+    FoodDefinition {
+        kind: FoodKind::Bonus,
+        spawn_weight: 20,
+        lifetime: Some(6.0),
+        score_multiplier: 3,
+        growth: 1,
+        frame_interval: 0.15,
+        tint: 0xFF80C0,
+        timed_effect: None,
+        wander_speed: 0.0,
+    },
+    FoodDefinition {
+        kind: FoodKind::Golden,
+        spawn_weight: 10,
+        lifetime: Some(8.0),
+        score_multiplier: 2,
+        growth: 3,
+        frame_interval: 0.25,
+        tint: 0xFFD700,
+        timed_effect: None,
+        wander_speed: 0.0,
+    },
+    FoodDefinition {
+        kind: FoodKind::Shrink,
+        spawn_weight: 10,
+        lifetime: Some(10.0),
+        score_multiplier: 1,
+        growth: -2,
+        frame_interval: 0.5,
+        tint: 0xA070FF,
+        timed_effect: None,
+        wander_speed: 0.0,
+    },
+    FoodDefinition {
+        kind: FoodKind::Speed,
+        spawn_weight: 10,
+        lifetime: Some(8.0),
+        score_multiplier: 1,
+        growth: 1,
+        frame_interval: 0.1,
+        tint: 0x60E0FF,
+        timed_effect: Some((TimedEffect::SpeedRush, SPEED_RUSH_SECONDS)),
+        wander_speed: 0.0,
+    },
+    FoodDefinition {
+        kind: FoodKind::Prey,
+        spawn_weight: 10,
+        lifetime: Some(15.0),
+        score_multiplier: 4,
+        growth: 1,
+        frame_interval: 0.2,
+        tint: 0x80FF80,
+        timed_effect: None,
+        wander_speed: 25.0,
+    },
+];
+
+pub fn food_definition(kind: FoodKind) -> &'static FoodDefinition {
+    FOOD_CATALOGUE
+        .iter()
+        .find(|definition| definition.kind == kind)
+        .unwrap_or_else(|| panic!("Food {:?} is missing from the catalogue", kind))
+}
+
+/// Picks the kind of the next extra food item, weighted by spawn weight
+pub fn roll_food_kind(rng: &mut impl Rng) -> FoodKind {
+    let total_weight: u32 = FOOD_CATALOGUE.iter().map(|definition| definition.spawn_weight).sum();
+    let mut roll = rng.random_range(0..total_weight);
+
+    for definition in FOOD_CATALOGUE.iter() {
+        if roll < definition.spawn_weight {
+            return definition.kind;
+        }
+        roll -= definition.spawn_weight;
+    }
+
+    FoodKind::Normal
+}
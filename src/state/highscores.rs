@@ -0,0 +1,129 @@
+use crate::state::constants::state::{HIGH_SCORES_FILE, MAX_HIGH_SCORES};
+use crate::state::perks::PERK_REGISTRY;
+use crate::state::structs::{Perk, PerkPick};
+
+/// A finished run worth remembering, together with the build that got there
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    pub build: Vec<PerkPick>,
+}
+
+/// Reads the high score table, best first. A missing or unreadable file means no high scores yet.
+pub fn load_high_scores() -> Vec<HighScoreEntry> {
+    std::fs::read_to_string(HIGH_SCORES_FILE)
+        .map(|text| parse_high_scores(&text))
+        .unwrap_or_default()
+}
+
+/// Adds a finished run to the table if it makes the cut, and saves the table
+pub fn record_high_score(high_scores: &mut Vec<HighScoreEntry>, entry: HighScoreEntry) {
+    let position = high_scores.iter().position(|existing| entry.score > existing.score).unwrap_or(high_scores.len());
+    if position >= MAX_HIGH_SCORES {
+        return;
+    }
+
+    high_scores.insert(position, entry);
+    high_scores.truncate(MAX_HIGH_SCORES);
+
+    if let Err(e) = std::fs::write(HIGH_SCORES_FILE, format_high_scores(high_scores)) {
+        eprintln!("Failed to save high scores to {}: {}", HIGH_SCORES_FILE, e);
+    }
+}
+
+/// One entry per line: the score, then each perk pick as `perk@score@seconds`, e.g.
+/// `4200 SpeedBoost@1000@31.5 CurseOfGlossy@2000@58.2`
+fn format_high_scores(high_scores: &[HighScoreEntry]) -> String {
+    high_scores
+        .iter()
+        .map(|entry| {
+            let mut line = entry.score.to_string();
+            for pick in entry.build.iter() {
+                line.push_str(&format!(" {:?}@{}@{:.1}", pick.perk, pick.score, pick.time));
+            }
+            line + "\n"
+        })
+        .collect()
+}
+
+/// Skips lines it can't make sense of, such as ones naming a perk that no longer exists
+fn parse_high_scores(text: &str) -> Vec<HighScoreEntry> {
+    let mut high_scores: Vec<HighScoreEntry> = text
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let score = fields.next()?.parse().ok()?;
+            let build = fields.map(parse_perk_pick).collect::<Option<Vec<_>>>()?;
+            Some(HighScoreEntry { score, build })
+        })
+        .collect();
+
+    high_scores.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+    high_scores.truncate(MAX_HIGH_SCORES);
+    high_scores
+}
+
+fn parse_perk_pick(field: &str) -> Option<PerkPick> {
+    let mut parts = field.split('@');
+    let name = parts.next()?;
+    let perk = perk_from_name(name)?;
+    let score = parts.next()?.parse().ok()?;
+    let time = parts.next()?.parse().ok()?;
+    Some(PerkPick { perk, score, time })
+}
+
+fn perk_from_name(name: &str) -> Option<Perk> {
+    PERK_REGISTRY
+        .iter()
+        .map(|definition| definition.perk)
+        .find(|perk| format!("{:?}", perk) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32, build: &[(Perk, u32, f32)]) -> HighScoreEntry {
+        HighScoreEntry {
+            score,
+            build: build.iter().map(|&(perk, score, time)| PerkPick { perk, score, time }).collect(),
+        }
+    }
+
+    #[test]
+    fn high_scores_survive_a_round_trip() {
+        let high_scores = vec![
+            entry(4200, &[(Perk::SpeedBoost, 1000, 31.5), (Perk::CurseOfGlossy, 2500, 58.2)]),
+            entry(1800, &[(Perk::DoubleScore, 1000, 12.0)]),
+            entry(300, &[]),
+        ];
+
+        let text = format_high_scores(&high_scores);
+        assert_eq!(text.lines().next(), Some("4200 SpeedBoost@1000@31.5 CurseOfGlossy@2500@58.2"));
+        assert_eq!(parse_high_scores(&text), high_scores);
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let text = "\
+            1500 SpeedBoost@1000@20.0\n\
+            not a score\n\
+            900 NoSuchPerk@500@3.0\n\
+            2000 DoubleScore@1000\n\
+            700 DoubleScore@many@3.0\n\
+            \n\
+            2500\n";
+
+        assert_eq!(parse_high_scores(text), vec![entry(2500, &[]), entry(1500, &[(Perk::SpeedBoost, 1000, 20.0)])]);
+    }
+
+    #[test]
+    fn only_the_best_scores_are_kept_in_order() {
+        let text: String = (1..=MAX_HIGH_SCORES as u32 + 5).map(|score| format!("{}\n", score * 100)).collect();
+        let high_scores = parse_high_scores(&text);
+
+        assert_eq!(high_scores.len(), MAX_HIGH_SCORES);
+        assert_eq!(high_scores[0].score, (MAX_HIGH_SCORES as u32 + 5) * 100);
+        assert!(high_scores.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+}
@@ -1,29 +1,37 @@
-use crate::audio::manager::MusicId;
+use crate::ai::steer_ai_worms;
+use crate::audio::director::Scene;
 use crate::graphics::render::render_pixel_buffer;
 use crate::graphics::update::update_pixel_buffer;
 use crate::input::handler::handle_user_input;
 use crate::state::constants::state::FRAME_RATE_SLEEP_DURATION;
 use crate::state::core::{execute_core_logic, CoreLogic};
 use crate::state::structs::GameState;
+use crate::state::title::run_title_screen;
 use minifb::Key;
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::{thread, time};
 
-pub fn start_event_loop(mut game_state: GameState, core_logic_map: HashMap<String, Rc<RefCell<dyn CoreLogic>>>) {
+pub fn start_event_loop(mut game_state: GameState, core_logic_map: Vec<(&'static str, Rc<RefCell<dyn CoreLogic>>)>) {
+
+    if !run_title_screen(&mut game_state, &core_logic_map) {
+        return;
+    }
+
+    game_state.audio_manager.set_scene(Scene::Gameplay);
 
     // Main event loop: runs as long as the window is open and the Escape key is not pressed
-    while game_state.window.is_open() && !game_state.window.is_key_down(Key::Escape) {
+    while game_state.is_window_open() && !game_state.is_key_down(Key::Escape) {
 
-        if !game_state.audio_manager.is_music_playing() && !game_state.music_disabled {
-            game_state.audio_manager.play_music(MusicId::Music0)
-                .expect("Failed to play music");
-        }
+        // Advance music playlists and crossfades
+        game_state.audio_manager.update();
 
         // Handle basic user input, which influence player direction
         handle_user_input(&mut game_state);
 
+        // Let computer-controlled rivals pick their way
+        steer_ai_worms(&mut game_state);
+
         // Process game logic such as obstacle detection
         execute_core_logic(&mut game_state, &core_logic_map);
 
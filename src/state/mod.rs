@@ -2,5 +2,9 @@ pub mod r#loop;
 pub mod core;
 pub mod constants;
 pub mod structs;
+pub mod perks;
+pub mod food;
+pub mod highscores;
+pub mod title;
 
 
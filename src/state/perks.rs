@@ -0,0 +1,388 @@
+use crate::audio::manager::SfxId;
+use crate::state::constants::state::{FOOD_MAGNET_SECONDS, GHOST_SECONDS, INVINCIBILITY_SECONDS, MAX_PERK_OFFER_COUNT, SLOW_MOTION_SECONDS};
+use crate::state::constants::text::{
+    PERK_BULLET_TIME, PERK_CURSE_OF_GLOSSY, PERK_FOOD_MAGNET, PERK_GHOST_WORM, PERK_HUNGRY_WORM, PERK_IRON_WORM, PERK_LUCKY_WORM, PERK_NEED_4_SPEED,
+    PERK_SLIM_WORM, PERK_SLOW_WORM, PERK_TASTY_SNACKS,
+};
+use crate::state::structs::{GameState, Perk, PerkPick, TimedEffect};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    /// A strong buff that comes with a risk
+    Cursed,
+}
+
+impl Rarity {
+    /// Color the perk's name is drawn in
+    pub fn color(&self) -> u32 {
+        match self {
+            Rarity::Common => 0xFFFFFF,
+            Rarity::Uncommon => 0x4FC3F7,
+            Rarity::Rare => 0xFFD700,
+            Rarity::Cursed => 0xFF5555,
+        }
+    }
+
+    /// Relative chance of a perk being drawn for an offer
+    pub fn weight(&self) -> u32 {
+        match self {
+            Rarity::Common => 60,
+            Rarity::Uncommon => 30,
+            Rarity::Rare => 10,
+            Rarity::Cursed => 15,
+        }
+    }
+}
+
+/// Everything the game needs to know about a perk: how it is presented, when it may be offered
+/// and what it does once chosen
+pub struct PerkDefinition {
+    pub perk: Perk,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Frame of `perks.png` shown as the perk's card
+    pub icon_frame: usize,
+    pub rarity: Rarity,
+    /// How many times the perk can be taken in a single run, `None` meaning no limit
+    pub max_stacks: Option<usize>,
+    /// Perks that rule this one out once taken, and the other way around
+    pub incompatible_with: &'static [Perk],
+    pub chosen_sfx: SfxId,
+    /// Applies the perk's lasting effect to the game state
+    pub apply: fn(&mut GameState),
+    /// Effect started when the perk is chosen, and for how many seconds it lasts
+    pub timed_effect: Option<(TimedEffect, f32)>,
+}
+
+pub static PERK_REGISTRY: &[PerkDefinition] = &[
+    PerkDefinition {
+        perk: Perk::SpeedBoost,
+        name: PERK_NEED_4_SPEED.0,
+        description: PERK_NEED_4_SPEED.1,
+        icon_frame: 0,
+        rarity: Rarity::Common,
+        max_stacks: Some(5),
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkNeedForSpeed,
+        apply: apply_speed_boost,
+        timed_effect: None,
+    },
+    PerkDefinition {
+        perk: Perk::DoubleScore,
+        name: PERK_HUNGRY_WORM.0,
+        description: PERK_HUNGRY_WORM.1,
+        icon_frame: 1,
+        rarity: Rarity::Uncommon,
+        max_stacks: Some(3),
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkHungryWorm,
+        apply: apply_double_score,
+        timed_effect: None,
+    },
+    PerkDefinition {
+        perk: Perk::TastySnacks,
+        name: PERK_TASTY_SNACKS.0,
+        description: PERK_TASTY_SNACKS.1,
+        icon_frame: 2,
+        rarity: Rarity::Common,
+        max_stacks: Some(5),
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkHungryWorm,
+        apply: apply_tasty_snacks,
+        timed_effect: None,
+    },
+    PerkDefinition {
+        perk: Perk::SlimWorm,
+        name: PERK_SLIM_WORM.0,
+        description: PERK_SLIM_WORM.1,
+        icon_frame: 3,
+        rarity: Rarity::Common,
+        max_stacks: None,
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkSlowDown,
+        apply: apply_slim_worm,
+        timed_effect: None,
+    },
+    PerkDefinition {
+        perk: Perk::SlowWorm,
+        name: PERK_SLOW_WORM.0,
+        description: PERK_SLOW_WORM.1,
+        icon_frame: 4,
+        rarity: Rarity::Uncommon,
+        max_stacks: Some(3),
+        incompatible_with: &[Perk::SpeedBoost],
+        chosen_sfx: SfxId::PerkSlowDown,
+        apply: apply_slow_worm,
+        timed_effect: None,
+    },
+    PerkDefinition {
+        perk: Perk::LuckyWorm,
+        name: PERK_LUCKY_WORM.0,
+        description: PERK_LUCKY_WORM.1,
+        icon_frame: 5,
+        rarity: Rarity::Rare,
+        max_stacks: Some(2),
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkLucky,
+        apply: apply_lucky_worm,
+        timed_effect: None,
+    },
+    PerkDefinition {
+        perk: Perk::IronWorm,
+        name: PERK_IRON_WORM.0,
+        description: PERK_IRON_WORM.1,
+        icon_frame: 6,
+        rarity: Rarity::Rare,
+        max_stacks: None,
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkPowerUp,
+        apply: apply_nothing,
+        timed_effect: Some((TimedEffect::Invincibility, INVINCIBILITY_SECONDS)),
+    },
+    PerkDefinition {
+        perk: Perk::BulletTime,
+        name: PERK_BULLET_TIME.0,
+        description: PERK_BULLET_TIME.1,
+        icon_frame: 7,
+        rarity: Rarity::Uncommon,
+        max_stacks: None,
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkPowerUp,
+        apply: apply_nothing,
+        timed_effect: Some((TimedEffect::SlowMotion, SLOW_MOTION_SECONDS)),
+    },
+    PerkDefinition {
+        perk: Perk::GhostWorm,
+        name: PERK_GHOST_WORM.0,
+        description: PERK_GHOST_WORM.1,
+        icon_frame: 8,
+        rarity: Rarity::Uncommon,
+        max_stacks: None,
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkPowerUp,
+        apply: apply_nothing,
+        timed_effect: Some((TimedEffect::Ghost, GHOST_SECONDS)),
+    },
+    PerkDefinition {
+        perk: Perk::FoodMagnet,
+        name: PERK_FOOD_MAGNET.0,
+        description: PERK_FOOD_MAGNET.1,
+        icon_frame: 9,
+        rarity: Rarity::Common,
+        max_stacks: None,
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkPowerUp,
+        apply: apply_nothing,
+        timed_effect: Some((TimedEffect::FoodMagnet, FOOD_MAGNET_SECONDS)),
+    },
+    PerkDefinition {
+        perk: Perk::CurseOfGlossy,
+        name: PERK_CURSE_OF_GLOSSY.0,
+        description: PERK_CURSE_OF_GLOSSY.1,
+        icon_frame: 10,
+        rarity: Rarity::Cursed,
+        max_stacks: Some(2),
+        incompatible_with: &[],
+        chosen_sfx: SfxId::PerkCursed,
+        apply: apply_curse_of_glossy,
+        timed_effect: None,
+    },
+];
+
+/// For perks whose only effect is timed
+fn apply_nothing(_game_state: &mut GameState) {}
+
+fn apply_speed_boost(game_state: &mut GameState) {
+    game_state.player.move_interval *= 0.8;
+}
+
+fn apply_double_score(game_state: &mut GameState) {
+    game_state.food_score_value *= 2;
+}
+
+fn apply_tasty_snacks(game_state: &mut GameState) {
+    game_state.food_score_value += 50;
+}
+
+fn apply_slim_worm(game_state: &mut GameState) {
+    // The last remaining segment becomes the new tail, and head, one body segment and tail always stay
+    let length = game_state.player.body.len().saturating_sub(3).max(3);
+    game_state.player.body.truncate(length);
+}
+
+/// Shiny hazards start appearing once the curse is in `perk_history`, see `core::hazards`
+fn apply_curse_of_glossy(game_state: &mut GameState) {
+    game_state.food_score_value *= 3;
+}
+
+fn apply_slow_worm(game_state: &mut GameState) {
+    game_state.player.move_interval *= 1.15;
+}
+
+fn apply_lucky_worm(game_state: &mut GameState) {
+    game_state.perk_offer_count = (game_state.perk_offer_count + 1).min(MAX_PERK_OFFER_COUNT);
+}
+
+/// Applies a chosen perk and adds it to the run's build
+pub fn take_perk(game_state: &mut GameState, perk: Perk) {
+    let definition = perk_definition(perk);
+    (definition.apply)(game_state);
+    if let Some((effect, seconds)) = definition.timed_effect {
+        game_state.player.start_timed_effect(effect, seconds);
+    }
+    game_state.perk_history.push(PerkPick {
+        perk,
+        score: game_state.score,
+        time: game_state.run_time,
+    });
+    if let Err(e) = game_state.audio_manager.play_sfx(definition.chosen_sfx, None) {
+        eprintln!("Warning: Failed to play perk chosen sound effect: {}", e);
+    }
+}
+
+/// The perk that grants `effect`, used to show the effect's icon
+pub fn timed_effect_perk(effect: TimedEffect) -> Option<&'static PerkDefinition> {
+    PERK_REGISTRY
+        .iter()
+        .find(|definition| definition.timed_effect.is_some_and(|(timed_effect, _)| timed_effect == effect))
+}
+
+pub fn perk_definition(perk: Perk) -> &'static PerkDefinition {
+    PERK_REGISTRY
+        .iter()
+        .find(|definition| definition.perk == perk)
+        .unwrap_or_else(|| panic!("Perk {:?} is missing from the registry", perk))
+}
+
+/// Perks that may still be offered, given the perks already taken this run
+pub fn available_perks(taken: &[Perk]) -> Vec<&'static PerkDefinition> {
+    PERK_REGISTRY
+        .iter()
+        .filter(|definition| {
+            let stacks = taken.iter().filter(|perk| **perk == definition.perk).count();
+            definition.max_stacks.is_none_or(|max_stacks| stacks < max_stacks)
+        })
+        .filter(|definition| {
+            !taken.iter().any(|perk| {
+                definition.incompatible_with.contains(perk) || perk_definition(*perk).incompatible_with.contains(&definition.perk)
+            })
+        })
+        .collect()
+}
+
+/// Draws up to `count` different perks from the available ones, weighted by rarity
+pub fn roll_perk_offers(taken: &[Perk], count: usize, rng: &mut impl Rng) -> Vec<Perk> {
+    let mut pool = available_perks(taken);
+    let mut offers = Vec::with_capacity(count);
+
+    while offers.len() < count && !pool.is_empty() {
+        let total_weight: u32 = pool.iter().map(|definition| definition.rarity.weight()).sum();
+        let mut roll = rng.random_range(0..total_weight);
+
+        let index = pool
+            .iter()
+            .position(|definition| {
+                let weight = definition.rarity.weight();
+                if roll < weight {
+                    true
+                } else {
+                    roll -= weight;
+                    false
+                }
+            })
+            .unwrap_or(pool.len() - 1);
+
+        offers.push(pool.remove(index).perk);
+    }
+
+    offers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::synth::parse_synth_definitions;
+    use crate::graphics::sprites::load_sprites_from_map;
+    use crate::state::constants::audio::SFX_DEFINITIONS_FILE;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn every_perk_has_a_card_of_its_own() {
+        let cards = load_sprites_from_map("assets/sprites/perks.png", 128, 112);
+        for (index, definition) in PERK_REGISTRY.iter().enumerate() {
+            assert!(definition.icon_frame < cards.len(), "{:?} has no card", definition.perk);
+            let shared = PERK_REGISTRY[..index].iter().find(|other| other.icon_frame == definition.icon_frame);
+            assert!(shared.is_none(), "{:?} shares its card with {:?}", definition.perk, shared.map(|other| other.perk));
+        }
+    }
+
+    #[test]
+    fn every_chosen_sound_can_be_synthesized() {
+        let definitions = parse_synth_definitions(&std::fs::read_to_string(SFX_DEFINITIONS_FILE).unwrap()).unwrap();
+        for definition in PERK_REGISTRY {
+            assert!(definitions.contains_key(definition.chosen_sfx.name()), "{:?} has no synth definition", definition.chosen_sfx);
+        }
+    }
+
+    #[test]
+    fn offers_are_drawn_in_proportion_to_rarity() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let draws = 50_000;
+        let mut counts = vec![0usize; PERK_REGISTRY.len()];
+        for _ in 0..draws {
+            let offer = roll_perk_offers(&[], 1, &mut rng);
+            counts[PERK_REGISTRY.iter().position(|definition| definition.perk == offer[0]).unwrap()] += 1;
+        }
+
+        let total_weight: u32 = PERK_REGISTRY.iter().map(|definition| definition.rarity.weight()).sum();
+        for (definition, count) in PERK_REGISTRY.iter().zip(counts) {
+            let expected = definition.rarity.weight() as f32 / total_weight as f32;
+            let observed = count as f32 / draws as f32;
+            assert!((observed - expected).abs() < 0.01, "{:?} drawn {} of the time, expected {}", definition.perk, observed, expected);
+        }
+    }
+
+    #[test]
+    fn a_perk_is_no_longer_offered_once_fully_stacked() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let all_but_one = [Perk::SpeedBoost; 4];
+        let fully_stacked = [Perk::SpeedBoost; 5];
+
+        let offered = |taken: &[Perk], rng: &mut StdRng| {
+            (0..500).any(|_| roll_perk_offers(taken, MAX_PERK_OFFER_COUNT, rng).contains(&Perk::SpeedBoost))
+        };
+        assert!(offered(&all_but_one, &mut rng));
+        assert!(!offered(&fully_stacked, &mut rng));
+    }
+
+    #[test]
+    fn incompatible_perks_rule_each_other_out() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..500 {
+            assert!(!roll_perk_offers(&[Perk::SpeedBoost], MAX_PERK_OFFER_COUNT, &mut rng).contains(&Perk::SlowWorm));
+            assert!(!roll_perk_offers(&[Perk::SlowWorm], MAX_PERK_OFFER_COUNT, &mut rng).contains(&Perk::SpeedBoost));
+        }
+    }
+
+    #[test]
+    fn an_offer_never_holds_the_same_perk_twice() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..500 {
+            let offers = roll_perk_offers(&[], MAX_PERK_OFFER_COUNT, &mut rng);
+            assert_eq!(offers.len(), MAX_PERK_OFFER_COUNT);
+            for (index, perk) in offers.iter().enumerate() {
+                assert!(!offers[..index].contains(perk), "{:?} offered twice in {:?}", perk, offers);
+            }
+        }
+
+        // Asking for more than there is offers everything, once
+        let mut offers = roll_perk_offers(&[], PERK_REGISTRY.len() + 3, &mut rng);
+        offers.sort_by_key(|perk| PERK_REGISTRY.iter().position(|definition| definition.perk == *perk));
+        assert!(offers.iter().eq(PERK_REGISTRY.iter().map(|definition| &definition.perk)), "{:?}", offers);
+    }
+}
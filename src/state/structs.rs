@@ -1,9 +1,17 @@
+use crate::ai::{AiPilot, ControllerKind, Difficulty};
 use crate::audio::manager::AudioManager;
 use crate::graphics::sprites::SpriteMaps;
-use minifb::Window;
-use std::collections::HashMap;
+use minifb::{Key, KeyRepeat, Window};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
-use crate::state::constants::graphics::{SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
+use crate::state::constants::graphics::{ART_WIDTH, SNAKE_BODY_HEIGHT, SNAKE_BODY_WIDTH};
+use crate::state::constants::state::{
+    BASE_FOOD_SCORE, DEFAULT_MOVE_INTERVAL, PERK_OFFER_COUNT, PERK_SCORE_THRESHOLDS, PLAYER_START, PLAYER_TWO_SKIN, PLAYER_TWO_START,
+    RIVAL_SKINS, RIVAL_STARTS, START_LENGTH,
+};
+use crate::state::highscores::HighScoreEntry;
 
 
 #[derive(Debug, Clone, Copy)]
@@ -12,7 +20,8 @@ pub struct Vector2D {
     pub y: f32,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     Right,
     Left,
@@ -20,6 +29,18 @@ pub enum Direction {
     Down,
 }
 
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Snake {
     pub direction: Direction,
     pub body: Vec<Vector2D>,
@@ -30,143 +51,563 @@ pub struct Snake {
     pub head_sprite_frame_index: usize,
     pub head_last_sprite_frame_index_update_time: Instant,
     pub food_near: bool,
+    /// Index into `SpriteMaps::worm_skins`, telling worms in the same arena apart
+    pub skin: usize,
+    pub active_effects: Vec<ActiveEffect>,
+    /// Steps taken so far, computer-controlled worms decide on their next move once per step
+    pub moves: u32,
 }
 
 impl Snake {
     pub fn new(x: f32, y: f32, initial_direction: Direction) -> Self {
 
-        let body = match initial_direction {
-            Direction::Right => vec![
-                Vector2D { x, y },
-                Vector2D { x: x - SNAKE_BODY_WIDTH * 2.0, y },
-                Vector2D { x: x - SNAKE_BODY_WIDTH * 3.0, y },
-            ],
-            Direction::Left => vec![
-                Vector2D { x, y },
-                Vector2D { x: x + SNAKE_BODY_WIDTH * 2.0, y },
-                Vector2D { x: x + SNAKE_BODY_WIDTH * 3.0, y },
-            ],
-            Direction::Down => vec![
-                Vector2D { x, y },
-                Vector2D { x, y: y - SNAKE_BODY_HEIGHT * 2.0 },
-                Vector2D { x, y: y - SNAKE_BODY_HEIGHT * 3.0 },
-            ],
-            Direction::Up => vec![
-                Vector2D { x, y },
-                Vector2D { x, y: y + SNAKE_BODY_HEIGHT * 2.0 },
-                Vector2D { x, y: y + SNAKE_BODY_HEIGHT * 3.0 },
-            ],
+        // The neck leaves a segment's gap behind the head
+        let (step_x, step_y) = match initial_direction {
+            Direction::Right => (-SNAKE_BODY_WIDTH, 0.0),
+            Direction::Left => (SNAKE_BODY_WIDTH, 0.0),
+            Direction::Down => (0.0, -SNAKE_BODY_HEIGHT),
+            Direction::Up => (0.0, SNAKE_BODY_HEIGHT),
         };
+        let body = std::iter::once(Vector2D { x, y })
+            .chain((2..=START_LENGTH).map(|segment| Vector2D { x: x + step_x * segment as f32, y: y + step_y * segment as f32 }))
+            .collect();
 
         Snake {
             direction: initial_direction,
             body,
             move_timer: 0.0,
-            move_interval: 0.1, // Default is 10 moves per second
+            move_interval: DEFAULT_MOVE_INTERVAL,
             body_sprite_frame_index: 0,
             body_last_sprite_frame_index_update_time: Instant::now(),
             head_sprite_frame_index: 0,
             head_last_sprite_frame_index_update_time: Instant::now(),
             food_near: false,
+            skin: 0,
+            active_effects: Vec::new(),
+            moves: 0,
+        }
+    }
+
+    /// Start a timed effect, or top it back up to `seconds` if it is already running
+    pub fn start_timed_effect(&mut self, effect: TimedEffect, seconds: f32) {
+        match self.active_effects.iter_mut().find(|active| active.effect == effect) {
+            Some(active) => active.remaining = active.remaining.max(seconds),
+            None => self.active_effects.push(ActiveEffect { effect, remaining: seconds }),
         }
     }
+
+    pub fn has_timed_effect(&self, effect: TimedEffect) -> bool {
+        self.active_effects.iter().any(|active| active.effect == effect)
+    }
+}
+
+/// How a worm other than the player's is steered
+pub enum RivalControl {
+    /// Second player on the arrow keys
+    Keyboard,
+    Ai(AiPilot),
 }
 
+/// Any worm sharing the arena with the player's
+pub struct Rival {
+    pub snake: Snake,
+    pub score: u32,
+    pub alive: bool,
+    pub control: RivalControl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    Solo,
+    /// Two players on one keyboard, the first to win `rounds_to_win` rounds takes the match
+    Versus { rounds_to_win: u32 },
+    /// The title screen's attract mode, the player's worm steered by `GameState::autopilot`
+    Demo,
+}
+
+/// What ended a worm's run. The arena wraps around, so there are no walls to run into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum DeathCause {
+    SelfCollision,
+    /// Ran into another worm
+    WormCollision,
+    Hazard,
+}
+
+/// Which worm did something, e.g. ate a piece of food
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WormId {
+    Player,
+    Rival(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FoodKind {
+    Normal,
+    Bonus,
+    Golden,
+    Shrink,
+    Speed,
+    Prey,
+}
+
+#[derive(Clone)]
 pub struct Food {
+    pub kind: FoodKind,
     pub position: Vector2D,
-    pub is_active: bool,
+    /// Simulation seconds since the food appeared, which drives its animation and expiry
+    pub age: f32,
+    /// Direction moving food is heading in, in radians
+    pub heading: f32,
     pub food_sprite_frame_index: usize,
-    pub food_last_sprite_frame_index_update_time: Instant,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Perk {
     SpeedBoost,
     DoubleScore,
+    TastySnacks,
+    SlimWorm,
+    SlowWorm,
+    LuckyWorm,
+    IronWorm,
+    BulletTime,
+    GhostWorm,
+    FoodMagnet,
+    CurseOfGlossy,
+}
+
+/// Effects that only last for a while, mostly started by choosing a perk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimedEffect {
+    Invincibility,
+    SlowMotion,
+    Ghost,
+    FoodMagnet,
+    /// Granted by speed food rather than a perk
+    SpeedRush,
+}
+
+/// A shiny pickup left by the Curse of Glossy, which kills the worm on contact
+#[derive(Clone)]
+pub struct Hazard {
+    pub position: Vector2D,
+    /// Simulation seconds since the hazard appeared, which also drives its twinkle
+    pub age: f32,
+}
+
+/// One entry of a run's build log: which perk was taken, at what score and how many seconds into the run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerkPick {
+    pub perk: Perk,
+    pub score: u32,
+    pub time: f32,
+}
+
+/// Floating "+200 x3" text left where food was eaten
+#[derive(Clone)]
+pub struct ScorePopup {
+    pub position: Vector2D,
+    pub text: String,
+    pub age: f32,
+}
+
+#[derive(Clone)]
+pub struct ActiveEffect {
+    pub effect: TimedEffect,
+    /// Simulation seconds left before the effect wears off
+    pub remaining: f32,
 }
 
 pub struct GameState<'a> {
     pub player: Snake,
+    pub rivals: Vec<Rival>,
+    pub mode: GameMode,
+    /// Rounds won by player one and player two in the current versus match
+    pub round_wins: [u32; 2],
+    /// Computer-controlled rivals joining every solo run
+    pub ai_rivals: Vec<(ControllerKind, Difficulty)>,
+    /// Steers the player's worm in place of the keyboard
+    pub autopilot: Option<AiPilot>,
     pub sprites: SpriteMaps,
-    pub window_buffer: &'a mut Vec<u32>,
+    pub window_buffer: Vec<u32>,
     pub window_width: usize,
     pub window_height: usize,
-    pub window: &'a mut Window,
-    pub scaled_buffer: &'a mut Vec<u32>,
-    pub food: Food,
+    /// `None` for a headless game, which never sees a key press and is never drawn to the screen
+    pub window: Option<&'a mut Window>,
+    pub scaled_buffer: Vec<u32>,
+    /// Simulation seconds per frame for a headless game, instead of the time that actually passed
+    pub fixed_delta_time: Option<f32>,
+    pub foods: Vec<Food>,
+    pub food_spawn_timer: f32,
     pub delta_time: f32,
     pub last_frame_time: Option<Instant>,
     pub game_over: bool,
+    /// Why the player's run ended, set along with `game_over`
+    pub death_cause: Option<DeathCause>,
     pub score: u32,
     pub globe_sprite_frame_index: usize,
     pub globe_last_sprite_frame_update_time: Instant,
     pub stars_offset_x: usize,
     pub stars_last_sprite_frame_update_time: Instant,
     pub stars_sprite_frame_index: usize,
-    pub perk_eligibility: bool,
+    /// Perk picks earned by crossing score thresholds but not made yet
+    pub pending_perk_picks: usize,
     pub selected_perk: Option<usize>,
     pub food_score_value: u32,
-    pub perk_history: HashMap<u32, Perk>,
+    pub perk_history: Vec<PerkPick>,
+    /// Number of score thresholds crossed this run
+    pub perk_thresholds_crossed: usize,
     pub perk_required_score: u32,
+    pub perk_offer_count: usize,
+    pub hazards: Vec<Hazard>,
+    pub hazard_spawn_timer: f32,
+    pub run_time: f32,
+    /// Multiplier applied to food score, raised by eating in quick succession
+    pub combo: u32,
+    /// Seconds left before the combo drops a step
+    pub combo_timer: f32,
+    pub score_popups: Vec<ScorePopup>,
+    pub high_scores: Vec<HighScoreEntry>,
+    pub rng: StdRng,
     pub audio_manager: AudioManager,
-    pub music_disabled: bool
 }
 
 impl<'a> GameState<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         player: Snake,
         sprites: SpriteMaps,
-        window_buffer: &'a mut Vec<u32>,
         window_width: usize,
         window_height: usize,
-        window: &'a mut Window,
-        scaled_buffer: &'a mut Vec<u32>,
-        audio_manager: AudioManager
+        window: Option<&'a mut Window>,
+        audio_manager: AudioManager,
+        high_scores: Vec<HighScoreEntry>,
+        seed: u64
     ) -> Self {
         GameState {
             player,
+            rivals: Vec::new(),
+            mode: GameMode::Solo,
+            round_wins: [0, 0],
+            ai_rivals: Vec::new(),
+            autopilot: None,
             sprites,
-            window_buffer,
+            window_buffer: vec![0; ART_WIDTH * ART_WIDTH],
             window_width,
             window_height,
             window,
-            scaled_buffer,
+            scaled_buffer: vec![0; window_width * window_height],
+            fixed_delta_time: None,
             delta_time: 0.0,
             last_frame_time: None,
             game_over: false,
+            death_cause: None,
             score: 0,
             globe_sprite_frame_index: 0,
-            food: Food {
-                position: Vector2D { x: 100.0, y: 100.0 },
-                is_active: false,
-                food_sprite_frame_index: 0,
-                food_last_sprite_frame_index_update_time: Instant::now(),
-            },
+            foods: Vec::new(),
+            food_spawn_timer: 0.0,
             globe_last_sprite_frame_update_time: Instant::now(),
             stars_offset_x: 0,
             stars_last_sprite_frame_update_time: Instant::now(),
             stars_sprite_frame_index: 0,
-            perk_eligibility: false,
+            pending_perk_picks: 0,
             selected_perk: None,
-            food_score_value: 100, // Default score value for food is 100
-            perk_history: HashMap::new(),
-            perk_required_score: 1000, // Every 1000 points, a perk becomes available
+            food_score_value: BASE_FOOD_SCORE,
+            perk_history: Vec::new(),
+            perk_thresholds_crossed: 0,
+            perk_required_score: perk_threshold(0), // Score at which the next perk becomes available
+            perk_offer_count: PERK_OFFER_COUNT,
+            hazards: Vec::new(),
+            hazard_spawn_timer: 0.0,
+            run_time: 0.0,
+            combo: 1,
+            combo_timer: 0.0,
+            score_popups: Vec::new(),
+            high_scores,
+            rng: StdRng::seed_from_u64(seed),
             audio_manager,
-            music_disabled: false,
         }
     }
 
     pub fn restart_level(&mut self) {
-        self.player = Snake::new(40.0, 150.0, Direction::Right);
-        self.food = Food {
-            position: Vector2D { x: 100.0, y: 100.0 },
-            is_active: false,
-            food_sprite_frame_index: 0,
-            food_last_sprite_frame_index_update_time: Instant::now(),
+        self.player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        self.rivals = match self.mode {
+            GameMode::Solo | GameMode::Demo => self
+                .ai_rivals
+                .iter()
+                .enumerate()
+                .map(|(index, &(kind, difficulty))| {
+                    let (x, y) = RIVAL_STARTS[index % RIVAL_STARTS.len()];
+                    let direction = if x > ART_WIDTH as f32 / 2.0 { Direction::Left } else { Direction::Right };
+                    let mut snake = Snake::new(x, y, direction);
+                    snake.skin = RIVAL_SKINS[index % RIVAL_SKINS.len()];
+                    Rival { snake, score: 0, alive: true, control: RivalControl::Ai(AiPilot::new(kind, difficulty)) }
+                })
+                .collect(),
+            GameMode::Versus { .. } => {
+                let mut player_two = Snake::new(PLAYER_TWO_START.0, PLAYER_TWO_START.1, Direction::Left);
+                player_two.skin = PLAYER_TWO_SKIN;
+                vec![Rival { snake: player_two, score: 0, alive: true, control: RivalControl::Keyboard }]
+            }
         };
+        self.foods.clear();
+        self.food_spawn_timer = 0.0;
         self.score = 0;
+        self.food_score_value = BASE_FOOD_SCORE;
+        self.perk_history.clear();
+        self.pending_perk_picks = 0;
+        self.perk_thresholds_crossed = 0;
+        self.perk_required_score = perk_threshold(0);
+        self.perk_offer_count = PERK_OFFER_COUNT;
+        self.hazards.clear();
+        self.hazard_spawn_timer = 0.0;
+        self.run_time = 0.0;
+        self.combo = 1;
+        self.combo_timer = 0.0;
+        self.score_popups.clear();
         self.game_over = false;
+        self.death_cause = None;
         self.globe_sprite_frame_index = 0;
         self.globe_last_sprite_frame_update_time = Instant::now();
     }
+
+    /// Switch to a versus match of `rounds_to_win` winning rounds, starting its first round
+    pub fn start_versus(&mut self, rounds_to_win: u32) {
+        self.mode = GameMode::Versus { rounds_to_win };
+        self.round_wins = [0, 0];
+        self.restart_level();
+    }
+
+    pub fn is_window_open(&self) -> bool {
+        self.window.as_ref().is_some_and(|window| window.is_open())
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.window.as_ref().is_some_and(|window| window.is_key_down(key))
+    }
+
+    pub fn is_key_pressed(&self, key: Key, repeat: KeyRepeat) -> bool {
+        self.window.as_ref().is_some_and(|window| window.is_key_pressed(key, repeat))
+    }
+
+    pub fn keys_down(&self) -> Vec<Key> {
+        self.window.as_ref().map_or_else(Vec::new, |window| window.get_keys())
+    }
+
+    pub fn keys_pressed(&self) -> Vec<Key> {
+        self.window.as_ref().map_or_else(Vec::new, |window| window.get_keys_pressed(KeyRepeat::No))
+    }
+
+    /// Adds a computer-controlled rival to every solo run from the next one on
+    pub fn add_ai_rival(&mut self, kind: ControllerKind, difficulty: Difficulty) {
+        self.ai_rivals.push((kind, difficulty));
+    }
+
+    pub fn is_versus(&self) -> bool {
+        matches!(self.mode, GameMode::Versus { .. })
+    }
+
+    pub fn snake(&self, worm: WormId) -> &Snake {
+        match worm {
+            WormId::Player => &self.player,
+            WormId::Rival(index) => &self.rivals[index].snake,
+        }
+    }
+
+    pub fn snake_mut(&mut self, worm: WormId) -> &mut Snake {
+        match worm {
+            WormId::Player => &mut self.player,
+            WormId::Rival(index) => &mut self.rivals[index].snake,
+        }
+    }
+
+    /// Every worm still in play, the player's first
+    pub fn living_worms(&self) -> Vec<WormId> {
+        let player = (!self.game_over).then_some(WormId::Player);
+        let rivals = self.rivals.iter().enumerate().filter(|(_, rival)| rival.alive).map(|(index, _)| WormId::Rival(index));
+        player.into_iter().chain(rivals).collect()
+    }
+
+    /// Every living worm's snake, the player's first
+    pub fn living_snakes_mut(&mut self) -> impl Iterator<Item = &mut Snake> {
+        let rivals = self.rivals.iter_mut().filter(|rival| rival.alive).map(|rival| &mut rival.snake);
+        std::iter::once(&mut self.player).chain(rivals)
+    }
+
+    /// Ends the round for `worm`: a game over for the player, out of play for a rival
+    pub fn kill_worm(&mut self, worm: WormId, cause: DeathCause) {
+        match worm {
+            WormId::Player => {
+                self.game_over = true;
+                self.death_cause = Some(cause);
+            }
+            WormId::Rival(index) => self.rivals[index].alive = false,
+        }
+    }
+
+    /// Adds to the score, queueing a perk pick for every threshold crossed on the way
+    pub fn add_score(&mut self, points: u32) {
+        self.score += points;
+
+        while self.score >= self.perk_required_score {
+            self.pending_perk_picks += 1;
+            self.perk_thresholds_crossed += 1;
+            self.perk_required_score = perk_threshold(self.perk_thresholds_crossed);
+        }
+    }
+
+    /// How many times `perk` has been taken this run
+    pub fn perk_stacks(&self, perk: Perk) -> usize {
+        self.perk_history.iter().filter(|pick| pick.perk == perk).count()
+    }
+}
+
+/// Everything the simulation carries from one tick to the next, to go back to when a rollback plays a stretch of
+/// the game again with corrected inputs. Rivals' controllers, the background, the window and audio are left out.
+#[derive(Clone)]
+pub struct Snapshot {
+    player: Snake,
+    /// Snake, score and whether it's alive, for each rival in order
+    rivals: Vec<(Snake, u32, bool)>,
+    round_wins: [u32; 2],
+    foods: Vec<Food>,
+    food_spawn_timer: f32,
+    delta_time: f32,
+    game_over: bool,
+    death_cause: Option<DeathCause>,
+    score: u32,
+    pending_perk_picks: usize,
+    food_score_value: u32,
+    perk_history: Vec<PerkPick>,
+    perk_thresholds_crossed: usize,
+    perk_required_score: u32,
+    perk_offer_count: usize,
+    hazards: Vec<Hazard>,
+    hazard_spawn_timer: f32,
+    run_time: f32,
+    combo: u32,
+    combo_timer: f32,
+    score_popups: Vec<ScorePopup>,
+    rng: StdRng,
 }
 
+impl GameState<'_> {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            player: self.player.clone(),
+            rivals: self.rivals.iter().map(|rival| (rival.snake.clone(), rival.score, rival.alive)).collect(),
+            round_wins: self.round_wins,
+            foods: self.foods.clone(),
+            food_spawn_timer: self.food_spawn_timer,
+            delta_time: self.delta_time,
+            game_over: self.game_over,
+            death_cause: self.death_cause,
+            score: self.score,
+            pending_perk_picks: self.pending_perk_picks,
+            food_score_value: self.food_score_value,
+            perk_history: self.perk_history.clone(),
+            perk_thresholds_crossed: self.perk_thresholds_crossed,
+            perk_required_score: self.perk_required_score,
+            perk_offer_count: self.perk_offer_count,
+            hazards: self.hazards.clone(),
+            hazard_spawn_timer: self.hazard_spawn_timer,
+            run_time: self.run_time,
+            combo: self.combo,
+            combo_timer: self.combo_timer,
+            score_popups: self.score_popups.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+
+    /// Puts the game back the way it was when `snapshot` was taken. The rivals must be the same ones as then.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.player = snapshot.player.clone();
+        for (rival, (snake, score, alive)) in self.rivals.iter_mut().zip(snapshot.rivals.iter()) {
+            rival.snake = snake.clone();
+            rival.score = *score;
+            rival.alive = *alive;
+        }
+        self.round_wins = snapshot.round_wins;
+        self.foods = snapshot.foods.clone();
+        self.food_spawn_timer = snapshot.food_spawn_timer;
+        self.delta_time = snapshot.delta_time;
+        self.game_over = snapshot.game_over;
+        self.death_cause = snapshot.death_cause;
+        self.score = snapshot.score;
+        self.pending_perk_picks = snapshot.pending_perk_picks;
+        self.food_score_value = snapshot.food_score_value;
+        self.perk_history = snapshot.perk_history.clone();
+        self.perk_thresholds_crossed = snapshot.perk_thresholds_crossed;
+        self.perk_required_score = snapshot.perk_required_score;
+        self.perk_offer_count = snapshot.perk_offer_count;
+        self.hazards = snapshot.hazards.clone();
+        self.hazard_spawn_timer = snapshot.hazard_spawn_timer;
+        self.run_time = snapshot.run_time;
+        self.combo = snapshot.combo;
+        self.combo_timer = snapshot.combo_timer;
+        self.score_popups = snapshot.score_popups.clone();
+        self.rng = snapshot.rng.clone();
+    }
+}
+
+/// Score needed for the perk after `thresholds_crossed` earlier ones. Follows `PERK_SCORE_THRESHOLDS`,
+/// then keeps adding the gap between its last two entries.
+pub fn perk_threshold(thresholds_crossed: usize) -> u32 {
+    let last_index = PERK_SCORE_THRESHOLDS.len() - 1;
+    if thresholds_crossed <= last_index {
+        return PERK_SCORE_THRESHOLDS[thresholds_crossed];
+    }
+
+    let last_gap = PERK_SCORE_THRESHOLDS[last_index] - PERK_SCORE_THRESHOLDS[last_index - 1];
+    PERK_SCORE_THRESHOLDS[last_index] + last_gap * (thresholds_crossed - last_index) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_state() -> GameState<'static> {
+        let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+        GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), 1)
+    }
+
+    #[test]
+    fn perk_thresholds_escalate_then_keep_the_last_gap() {
+        let thresholds: Vec<u32> = (0..6).map(perk_threshold).collect();
+        assert_eq!(thresholds, vec![1000, 2500, 5000, 7500, 10000, 12500]);
+    }
+
+    #[test]
+    fn a_gain_that_overshoots_a_threshold_still_crosses_it() {
+        let mut game_state = game_state();
+
+        // Tasty Snacks followed by Hungry Worm makes food worth 300, which never lands on 1000 or 2500 exactly
+        for _ in 0..4 {
+            game_state.add_score(300);
+        }
+        assert_eq!(game_state.score, 1200);
+        assert_eq!(game_state.pending_perk_picks, 1);
+        assert_eq!(game_state.perk_required_score, 2500);
+
+        for _ in 0..4 {
+            game_state.add_score(300);
+        }
+        assert_eq!(game_state.pending_perk_picks, 1);
+
+        game_state.add_score(300);
+        assert_eq!(game_state.score, 2700);
+        assert_eq!(game_state.pending_perk_picks, 2);
+        assert_eq!(game_state.perk_required_score, 5000);
+    }
+
+    #[test]
+    fn one_large_gain_queues_a_pick_per_threshold_crossed() {
+        let mut game_state = game_state();
+        game_state.add_score(8000);
+
+        assert_eq!(game_state.pending_perk_picks, 4);
+        assert_eq!(game_state.perk_thresholds_crossed, 4);
+        assert_eq!(game_state.perk_required_score, 10000);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::ai::{steer_ai_worms, AiPilot, ControllerKind, Difficulty};
+use crate::audio::director::Scene;
+use crate::graphics::render::render_pixel_buffer;
+use crate::graphics::update::{draw_demo_screen, draw_title_screen};
+use crate::state::constants::state::{DEMO_IDLE_SECONDS, FRAME_RATE_SLEEP_DURATION};
+use crate::state::core::{execute_core_logic, CoreLogic};
+use crate::state::structs::{GameMode, GameState};
+use minifb::{Key, KeyRepeat};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Shows the title screen until the player starts a game, playing the demo whenever it sits idle.
+/// Returns false if the window was closed instead.
+pub fn run_title_screen(game_state: &mut GameState, core_logic_map: &[(&'static str, Rc<RefCell<dyn CoreLogic>>)]) -> bool {
+    game_state.audio_manager.set_scene(Scene::Title);
+    let mut idle_since = Instant::now();
+
+    while game_state.is_window_open() && !game_state.is_key_down(Key::Escape) {
+        game_state.audio_manager.update();
+
+        if game_state.is_key_pressed(Key::Space, KeyRepeat::No) || game_state.is_key_pressed(Key::Enter, KeyRepeat::No) {
+            return true;
+        }
+        if !game_state.keys_down().is_empty() {
+            idle_since = Instant::now();
+        }
+
+        if idle_since.elapsed().as_secs_f32() >= DEMO_IDLE_SECONDS {
+            run_demo(game_state, core_logic_map);
+            idle_since = Instant::now();
+            continue;
+        }
+
+        draw_title_screen(game_state);
+        render_pixel_buffer(game_state);
+        std::thread::sleep(std::time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+
+    false
+}
+
+/// Plays the game by itself, through the same core logic and renderer as a real run, until any key is pressed
+fn run_demo(game_state: &mut GameState, core_logic_map: &[(&'static str, Rc<RefCell<dyn CoreLogic>>)]) {
+    let mode = game_state.mode;
+    game_state.mode = GameMode::Demo;
+    game_state.autopilot = Some(AiPilot::new(ControllerKind::FloodFill, Difficulty::HARD));
+    game_state.restart_level();
+    game_state.last_frame_time = None;
+
+    while game_state.is_window_open() && game_state.keys_pressed().is_empty() {
+        game_state.audio_manager.update();
+
+        steer_ai_worms(game_state);
+        execute_core_logic(game_state, core_logic_map);
+
+        // No game over screen in the demo, the bot simply has another go
+        if game_state.game_over {
+            game_state.restart_level();
+        }
+
+        draw_demo_screen(game_state);
+        render_pixel_buffer(game_state);
+        std::thread::sleep(std::time::Duration::from_millis(FRAME_RATE_SLEEP_DURATION));
+    }
+
+    game_state.mode = mode;
+    game_state.autopilot = None;
+    game_state.restart_level();
+    game_state.audio_manager.set_scene(Scene::Title);
+}
\ No newline at end of file
@@ -0,0 +1,33 @@
+use space_worm::ai::{ControllerKind, Difficulty};
+use space_worm::gym::protocol::run_bot_protocol;
+
+/// A bot that doesn't look at the game: it turns left every few steps, going a little further between turns
+/// as it goes, until it runs into itself or the rival
+fn scripted_actions() -> String {
+    let turns = ["up", "left", "down", "right"];
+    (0..2000)
+        .map(|step| {
+            let side = 1 + step / 40;
+            format!("{{\"direction\": \"{}\"}}\n", turns[(step / side) % turns.len()])
+        })
+        .collect()
+}
+
+fn play(seed: u64) -> Vec<String> {
+    let mut output = Vec::new();
+    let rivals = [(ControllerKind::Greedy, Difficulty::NORMAL)];
+    run_bot_protocol(seed, &rivals, scripted_actions().as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn same_seed_plays_the_same_game_to_the_end() {
+    let first = play(42);
+    let second = play(42);
+
+    assert_eq!(first, second);
+
+    let last: serde_json::Value = serde_json::from_str(first.last().unwrap()).unwrap();
+    assert_eq!(last["game_over"], true);
+    assert!(first.len() > 1);
+}
@@ -0,0 +1,120 @@
+use space_worm::audio::manager::AudioManager;
+use space_worm::graphics::sprites::SpriteMaps;
+use space_worm::net::checksum::state_hash;
+use space_worm::net::lockstep::{Lockstep, NetError};
+use space_worm::net::session::{accept_peer, connect, greet_client, greet_host, MatchSettings, Peer};
+use space_worm::state::constants::state::PLAYER_START;
+use space_worm::state::core::versus::{settle_round, start_next_round};
+use space_worm::state::structs::{Direction, GameState, Snake, WormId};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::sync::{Arc, Barrier};
+use std::time::Duration;
+
+const SETTINGS: MatchSettings = MatchSettings { seed: 42, rounds_to_win: 3, input_delay: 3, rollback: false };
+
+/// How a peer's side of a test match should go
+#[derive(Clone, Copy)]
+struct Script {
+    ticks: u64,
+    /// Nudges this side's worm off course after the given tick, as a bug in the simulation might
+    tamper_after: Option<u64>,
+    /// Hangs up without a word after the given tick
+    hang_up_after: Option<u64>,
+}
+
+const FULL_MATCH: Script = Script { ticks: 1200, tamper_after: None, hang_up_after: None };
+
+/// A player who turns every so often, each side on its own rhythm
+fn scripted_direction(worm: WormId, tick: u64) -> Option<Direction> {
+    let (period, turns) = match worm {
+        WormId::Player => (37, [Direction::Up, Direction::Right, Direction::Down, Direction::Right]),
+        WormId::Rival(_) => (29, [Direction::Down, Direction::Left, Direction::Up, Direction::Left]),
+    };
+    tick.is_multiple_of(period).then(|| turns[(tick / period) as usize % turns.len()])
+}
+
+/// Plays `script` over `peer`, returning the hash of the game at the end and the session, which is kept open
+/// until both sides are done
+fn play(peer: Peer, worm: WormId, script: Script) -> Result<(u64, Lockstep), NetError> {
+    let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+    let mut game_state = GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), SETTINGS.seed);
+    game_state.start_versus(SETTINGS.rounds_to_win);
+    let mut lockstep = Lockstep::new(peer, worm, SETTINGS.input_delay);
+
+    while lockstep.tick() < script.ticks {
+        if script.hang_up_after.is_some_and(|tick| lockstep.tick() > tick) {
+            return Err(NetError::Disconnected);
+        }
+
+        lockstep.send_input(scripted_direction(worm, lockstep.tick() + SETTINGS.input_delay))?;
+        lockstep.poll()?;
+        if !lockstep.advance(&mut game_state)? {
+            std::thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+
+        if let Some(outcome) = settle_round(&mut game_state) {
+            start_next_round(&mut game_state, outcome);
+        }
+        if script.tamper_after.is_some_and(|tick| lockstep.tick() > tick) {
+            game_state.snake_mut(worm).body[0].y += 0.5;
+        }
+    }
+
+    Ok((state_hash(&game_state), lockstep))
+}
+
+/// Runs a host and a client as two separate threads talking over TCP on localhost, giving up if either hangs
+fn play_match(host_script: Script, client_script: Script) -> [Result<u64, NetError>; 2] {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let barrier = Arc::new(Barrier::new(2));
+    let (results, received) = mpsc::channel();
+
+    let host_barrier = barrier.clone();
+    let host_results = results.clone();
+    std::thread::spawn(move || {
+        let mut peer = accept_peer(&listener, || true).unwrap().unwrap();
+        greet_client(&mut peer, SETTINGS).unwrap();
+        let result = play(peer, WormId::Player, host_script);
+        host_barrier.wait();
+        host_results.send((0, result.map(|(hash, _)| hash))).unwrap();
+    });
+
+    std::thread::spawn(move || {
+        let mut peer = connect(&address).unwrap();
+        let settings = greet_host(&mut peer).unwrap();
+        assert_eq!(settings, SETTINGS);
+        let result = play(peer, WormId::Rival(0), client_script);
+        barrier.wait();
+        results.send((1, result.map(|(hash, _)| hash))).unwrap();
+    });
+
+    let mut outcome = [Err(NetError::Disconnected), Err(NetError::Disconnected)];
+    for _ in 0..2 {
+        let (side, result) = received.recv_timeout(Duration::from_secs(30)).expect("A peer hung instead of finishing");
+        outcome[side] = result;
+    }
+    outcome
+}
+
+#[test]
+fn both_sides_end_up_with_the_same_game() {
+    let [host, client] = play_match(FULL_MATCH, FULL_MATCH);
+    assert_eq!(host.unwrap(), client.unwrap());
+}
+
+#[test]
+fn hanging_up_stops_the_other_side() {
+    let [host, client] = play_match(Script { hang_up_after: Some(200), ..FULL_MATCH }, FULL_MATCH);
+    assert!(host.is_err());
+    assert_eq!(client, Err(NetError::Disconnected));
+}
+
+#[test]
+fn drifting_apart_is_noticed() {
+    let [host, client] = play_match(FULL_MATCH, Script { tamper_after: Some(100), ..FULL_MATCH });
+    assert!(host.is_err() && client.is_err());
+    assert!([host, client].iter().any(|result| matches!(result, Err(NetError::Desync { .. }))));
+}
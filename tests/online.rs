@@ -0,0 +1,65 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+const FRAMES: &str = "150";
+
+fn space_worm(args: &[&str]) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_space_worm"))
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start space_worm")
+}
+
+/// Everything the process printed, after checking it exited cleanly
+fn finish(child: Child) -> String {
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "exited with {}: {}{}", output.status, stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+/// Hosts a headless match on a port of the system's choosing, joins it from a second process, and returns what
+/// each side printed
+fn play_over_localhost(extra_host_args: &[&str]) -> (String, String) {
+    let mut host_args = vec!["--host", "0", "--headless", "--seed", "5", "--frames", FRAMES];
+    host_args.extend_from_slice(extra_host_args);
+    let mut host = space_worm(&host_args);
+
+    // The host says which port it got before it starts waiting
+    let mut waiting = String::new();
+    BufReader::new(host.stdout.as_mut().unwrap()).read_line(&mut waiting).unwrap();
+    let port = waiting.trim().rsplit(' ').next().unwrap().to_string();
+    assert!(port.parse::<u16>().is_ok(), "expected the port the host listens on, got {:?}", waiting);
+
+    let client = space_worm(&["--connect", &format!("127.0.0.1:{}", port), "--headless", "--frames", FRAMES]);
+    let client_output = finish(client);
+    (finish(host), client_output)
+}
+
+fn assert_in_sync(output: &str) {
+    let tick: u64 = output
+        .lines()
+        .find_map(|line| line.strip_prefix("In sync at tick "))
+        .unwrap_or_else(|| panic!("no sync report in {:?}", output))
+        .parse()
+        .unwrap();
+    assert!(tick >= FRAMES.parse().unwrap());
+}
+
+#[test]
+fn two_processes_play_a_rollback_match_over_localhost() {
+    let (host, client) = play_over_localhost(&[]);
+
+    assert_in_sync(&host);
+    assert_in_sync(&client);
+}
+
+#[test]
+fn two_processes_play_a_lockstep_match_over_localhost() {
+    let (host, client) = play_over_localhost(&["--lockstep"]);
+
+    assert_in_sync(&host);
+    assert_in_sync(&client);
+}
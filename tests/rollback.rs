@@ -0,0 +1,49 @@
+use space_worm::audio::manager::AudioManager;
+use space_worm::graphics::sprites::SpriteMaps;
+use space_worm::net::checksum::state_hash;
+use space_worm::net::harness::{run_rollback_harness, HarnessConfig, HarnessReport, LinkConditions};
+use space_worm::net::lockstep::simulate_tick;
+use space_worm::state::constants::net::{HARNESS_FRAMES, ROLLBACK_INPUT_DELAY};
+use space_worm::state::constants::state::PLAYER_START;
+use space_worm::state::core::initialize_online_core_logic_map;
+use space_worm::state::core::versus::{settle_round, start_next_round};
+use space_worm::state::structs::{Direction, GameState, Snake, WormId};
+
+/// A bad connection: a tenth of a second each way, give or take, and one message in ten lost
+const CONFIG: HarnessConfig = HarnessConfig {
+    conditions: LinkConditions { latency_ms: 100, jitter_ms: 30, loss: 0.1 },
+    seed: 7,
+    frames: HARNESS_FRAMES,
+    input_delay: ROLLBACK_INPUT_DELAY,
+};
+
+/// Plays the inputs of a harness run on a single game, the way lockstep would with them, up to the tick
+/// the harness peers ended on
+fn replay_in_lockstep(report: &HarnessReport) -> u64 {
+    let player = Snake::new(PLAYER_START.0, PLAYER_START.1, Direction::Right);
+    let mut game_state = GameState::new(player, SpriteMaps::new(), 0, 0, None, AudioManager::silent(), Vec::new(), CONFIG.seed);
+    game_state.start_versus(u32::MAX);
+    let core_logic = initialize_online_core_logic_map();
+
+    let [host_inputs, client_inputs] = &report.inputs;
+    for (&host, &client) in host_inputs.iter().zip(client_inputs) {
+        simulate_tick(&mut game_state, &core_logic, [(WormId::Player, host), (WormId::Rival(0), client)]);
+        if let Some(outcome) = settle_round(&mut game_state) {
+            start_next_round(&mut game_state, outcome);
+        }
+    }
+    state_hash(&game_state)
+}
+
+#[test]
+fn rollback_over_a_bad_connection_plays_the_same_game_as_lockstep() {
+    let report = run_rollback_harness(&CONFIG);
+
+    assert_eq!(report.outcome, Ok(()));
+    assert!(report.stats.iter().all(|stats| stats.rollbacks > 0), "{:?}", report.stats);
+    assert!(report.rounds_played.iter().all(|&rounds| rounds > 0), "{:?}", report.rounds_played);
+    assert!(report.inputs.iter().all(|inputs| inputs.len() as u64 == report.final_tick));
+
+    let lockstep_hash = replay_in_lockstep(&report);
+    assert_eq!(report.final_hashes, [lockstep_hash; 2]);
+}